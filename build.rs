@@ -0,0 +1,64 @@
+//! Checks that the fixed string arrays in `src/main.rs` stay sorted and free
+//! of duplicates, so entries are easy to find and cannot be added twice.
+
+use std::fs;
+use std::process;
+
+const SOURCE: &str = "src/main.rs";
+const SORTED_ARRAYS: [&str; 3] = [
+    "FIXED_EXCLUDED_STRINGS",
+    "FIXED_FRONTARY_KEY",
+    "FIXED_UI_KEY",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed={SOURCE}");
+    let source = match fs::read_to_string(SOURCE) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("error: failed to read {SOURCE}: {e}");
+            process::exit(1);
+        }
+    };
+
+    let mut errors = Vec::new();
+    for name in SORTED_ARRAYS {
+        let Some(entries) = array_entries(&source, name) else {
+            errors.push(format!("`{name}` not found in {SOURCE}"));
+            continue;
+        };
+        for pair in entries.windows(2) {
+            if pair[0] == pair[1] {
+                errors.push(format!("`{name}` lists \"{}\" twice", pair[0]));
+            } else if pair[0] > pair[1] {
+                errors.push(format!(
+                    "`{name}` is not sorted: \"{}\" must come before \"{}\"",
+                    pair[1], pair[0]
+                ));
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        for error in &errors {
+            println!("cargo:warning={error}");
+        }
+        eprintln!("error: keep the fixed arrays in {SOURCE} sorted and without duplicates");
+        process::exit(1);
+    }
+}
+
+/// Returns the contents of the string literals in `const <name>: &[&str] =
+/// &[...];`, as written in the source.
+fn array_entries<'a>(source: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    let start = source.find(&format!("const {name}:"))?;
+    let body_start = start + source[start..].find("&[")? + 2;
+    let body_end = body_start + source[body_start..].find("];")?;
+    Some(
+        source[body_start..body_end]
+            .lines()
+            .map(str::trim)
+            .filter_map(|line| line.strip_prefix('"')?.strip_suffix("\","))
+            .collect(),
+    )
+}
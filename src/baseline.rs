@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::Path;
+
+use chrono::{SecondsFormat, Utc};
+use serde_json::{Value, json};
+
+/// Reads the key set of a previous extraction run.
+///
+/// The file is either a JSON array of strings or an object whose `keys` field
+/// is such an array.
+pub(crate) fn read_baseline(path: &Path) -> Result<HashSet<String>, io::Error> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        Error::new(
+            e.kind(),
+            format!("Failed to read baseline {}: {e}", path.display()),
+        )
+    })?;
+    let json: Value = serde_json::from_str(&content)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("JSON error: {e}")))?;
+
+    let keys = match &json {
+        Value::Array(keys) => Some(keys),
+        Value::Object(map) => map.get("keys").and_then(Value::as_array),
+        _ => None,
+    };
+    keys.and_then(|keys| {
+        keys.iter()
+            .map(|key| key.as_str().map(ToString::to_string))
+            .collect::<Option<HashSet<_>>>()
+    })
+    .ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Invalid baseline {}: expected an array of strings or an object with `keys`",
+                path.display()
+            ),
+        )
+    })
+}
+
+/// Returns the keys of `current` that are not in `baseline`, i.e. the missing
+/// keys introduced since the baseline run.
+pub(crate) fn diff_missing_keys(
+    current: &HashSet<String>,
+    baseline: &HashSet<String>,
+) -> HashSet<String> {
+    current.difference(baseline).cloned().collect()
+}
+
+pub(crate) fn print_baseline_diff(baseline: &HashSet<String>, current: &HashSet<String>) {
+    println!("=== all_strings vs baseline ===");
+    print_key_list(
+        "Keys added since the baseline",
+        current.difference(baseline),
+    );
+    print_key_list(
+        "Keys removed since the baseline",
+        baseline.difference(current),
+    );
+    println!();
+}
+
+fn print_key_list<'a>(title: &str, keys: impl Iterator<Item = &'a String>) {
+    let mut keys: Vec<_> = keys.collect();
+    if keys.is_empty() {
+        println!("{title}: none");
+        return;
+    }
+    keys.sort();
+    println!("{title}:");
+    for key in keys {
+        println!("  - {key}");
+    }
+}
+
+/// Writes `keys` with run metadata so a later run can use the file as its
+/// `--baseline`.
+pub(crate) fn save_keys(
+    path: &Path,
+    keys: &HashSet<String>,
+    repo_commit: Option<&str>,
+) -> Result<(), io::Error> {
+    let mut keys: Vec<_> = keys.iter().collect();
+    keys.sort();
+    let json = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        "repo_commit": repo_commit,
+        "keys": keys,
+    });
+    let content = serde_json::to_string_pretty(&json)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("JSON error: {e}")))?;
+    fs::write(path, content + "\n")
+        .map_err(|e| Error::new(e.kind(), format!("Failed to write {}: {e}", path.display())))?;
+    eprintln!("💾 Saved {} keys to {}", keys.len(), path.display());
+    Ok(())
+}
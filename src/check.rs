@@ -0,0 +1,443 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::locale::{LocaleFormat, ReadOptions};
+
+/// Matches Rust format placeholders such as `{}`, `{0}`, `{name}`, and
+/// `{:>8}`, and printf-style `%s` and `%d`.
+static PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\w*(?::[^{}]*)?\}|%[sd]").expect("valid regex"));
+
+/// Matches `snake_case`, `camelCase`, and `PascalCase` identifiers. Each must
+/// have an underscore or an inner uppercase letter, since single words such as
+/// `save` or `Cancel` are common keys.
+static IDENTIFIER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        "^(?:",
+        "[a-z][a-z0-9]*(?:_[a-z0-9]+)+",      // snake_case
+        "|[a-z][a-z0-9]*(?:[A-Z][a-z0-9]*)+", // camelCase
+        "|[A-Z][a-z0-9]+(?:[A-Z][a-z0-9]*)+", // PascalCase
+        ")$",
+    ))
+    .expect("valid regex")
+});
+
+/// Named `--key-format` conventions and the patterns keys must fully match.
+const KEY_FORMATS: &[(&str, &str)] = &[
+    // the first letter is uppercase; anything may follow
+    ("title-case", r"[^\p{L}]*\p{Lu}.*"),
+    ("lower-kebab", "[a-z][a-z0-9-]*"),
+];
+
+/// Parses a `--key-format` value: the name of a convention in `KEY_FORMATS`
+/// or a regex that each key must match in full.
+pub(crate) fn parse_key_format(value: &str) -> Result<Regex, String> {
+    let pattern = KEY_FORMATS
+        .iter()
+        .find(|(name, _)| *name == value)
+        .map_or(value, |(_, pattern)| pattern);
+    Regex::new(pattern).map_err(|e| e.to_string())?;
+    Regex::new(&format!("^(?s:{pattern})$")).map_err(|e| e.to_string())
+}
+
+/// Prints the keys that do not follow the `--key-format` convention.
+pub(crate) fn print_key_format_violations(name: &str, keys: &HashSet<String>, format: &Regex) {
+    let mut violations: Vec<_> = keys.iter().filter(|key| !format.is_match(key)).collect();
+    if violations.is_empty() {
+        return;
+    }
+    violations.sort();
+
+    println!("⚠️ Keys in `{name}` not matching --key-format:");
+    for key in violations {
+        println!("  - {key:?}");
+    }
+    println!();
+}
+
+/// Returns the keys that look like code identifiers rather than UI text,
+/// e.g. `user_name` or `UserName`.
+pub(crate) fn find_identifier_keys(keys: &HashSet<String>) -> Vec<&String> {
+    let mut identifiers: Vec<_> = keys
+        .iter()
+        .filter(|key| IDENTIFIER_RE.is_match(key))
+        .collect();
+    identifiers.sort();
+    identifiers
+}
+
+pub(crate) fn print_identifier_keys(name: &str, keys: &HashSet<String>) {
+    let identifiers = find_identifier_keys(keys);
+    if identifiers.is_empty() {
+        return;
+    }
+
+    println!("⚠️ Keys in `{name}` that may be code identifiers rather than UI text:");
+    for key in identifiers {
+        println!("  - {key:?}");
+    }
+    println!();
+}
+
+/// Returns pairs of keys that are equal when lowercased but differ in case,
+/// e.g. `("Save", "save")`.
+pub(crate) fn find_case_duplicates(keys: &HashSet<String>) -> Vec<(String, String)> {
+    find_duplicates_by(keys, str::to_lowercase)
+}
+
+/// Returns pairs of keys that are equal when trimmed but differ in leading or
+/// trailing whitespace, e.g. `(" Save", "Save")`.
+pub(crate) fn find_whitespace_duplicates(keys: &HashSet<String>) -> Vec<(String, String)> {
+    find_duplicates_by(keys, |key| key.trim().to_string())
+}
+
+fn find_duplicates_by(
+    keys: &HashSet<String>,
+    normalize: impl Fn(&str) -> String,
+) -> Vec<(String, String)> {
+    let mut groups: BTreeMap<String, Vec<&String>> = BTreeMap::new();
+    for key in keys {
+        groups.entry(normalize(key)).or_default().push(key);
+    }
+
+    let mut duplicates = Vec::new();
+    for mut group in groups.into_values().filter(|group| group.len() > 1) {
+        group.sort();
+        for (i, first) in group.iter().enumerate() {
+            for second in &group[i + 1..] {
+                duplicates.push(((*first).clone(), (*second).clone()));
+            }
+        }
+    }
+    duplicates
+}
+
+pub(crate) fn print_case_duplicates(name: &str, keys: &HashSet<String>) {
+    let duplicates = find_case_duplicates(keys);
+    if duplicates.is_empty() {
+        return;
+    }
+
+    println!("⚠️ Keys in `{name}` that differ only by case:");
+    for (first, second) in duplicates {
+        println!("  - {first:?} / {second:?}");
+    }
+    println!();
+}
+
+pub(crate) fn print_whitespace_duplicates(name: &str, keys: &HashSet<String>) {
+    let duplicates = find_whitespace_duplicates(keys);
+    if duplicates.is_empty() {
+        return;
+    }
+
+    println!("⚠️ Keys in `{name}` that differ only by surrounding whitespace:");
+    for (first, second) in duplicates {
+        println!("  - {first:?} / {second:?}");
+    }
+    println!();
+}
+
+/// Returns the keys containing characters that text from a well-behaved tool
+/// would not: null bytes or the U+FFFD replacement character left by lossy
+/// decoding of malformed UTF-8. Each key is paired with the reason it is
+/// malformed.
+///
+/// Surrogate code points need no check: a `char` cannot hold one, and JSON
+/// parsing already rejects lone surrogate escapes such as `"\ud800"`.
+pub(crate) fn validate_utf8_keys(keys: &HashSet<String>) -> Vec<(&String, String)> {
+    let mut malformed: Vec<_> = keys
+        .iter()
+        .filter_map(|key| {
+            key.chars()
+                .find_map(|c| {
+                    if c == '\0' {
+                        Some("contains a null byte".to_string())
+                    } else if c == char::REPLACEMENT_CHARACTER {
+                        Some("contains U+FFFD, likely from malformed UTF-8".to_string())
+                    } else {
+                        None
+                    }
+                })
+                .map(|reason| (key, reason))
+        })
+        .collect();
+    malformed.sort();
+    malformed
+}
+
+pub(crate) fn print_malformed_keys(name: &str, keys: &HashSet<String>) {
+    let malformed = validate_utf8_keys(keys);
+    if malformed.is_empty() {
+        return;
+    }
+
+    println!("⚠️ Malformed keys in `{name}`:");
+    for (key, reason) in malformed {
+        println!("  - {key:?}: {reason}");
+    }
+    println!();
+}
+
+/// Warns when a fixed exclusion occurs, ignoring case, inside a kept key, which
+/// may mean the exclusion is broader than intended.
+pub(crate) fn warn_substring_exclusions(excluded: &[&str], kept: &HashSet<String>) {
+    let mut kept: Vec<_> = kept.iter().collect();
+    kept.sort();
+    let mut matches = Vec::new();
+    for exclusion in excluded {
+        let needle = exclusion.to_lowercase();
+        for key in &kept {
+            if key.as_str() != *exclusion && key.to_lowercase().contains(&needle) {
+                matches.push((exclusion, key));
+            }
+        }
+    }
+    if matches.is_empty() {
+        return;
+    }
+
+    println!("⚠️ Excluded strings that occur inside kept keys:");
+    for (exclusion, key) in matches {
+        println!("  - {exclusion:?} in {key:?}");
+    }
+    println!();
+}
+
+/// Maximum edit distance for a locale key to be suggested as a typo fix.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Computes the Levenshtein edit distance between `a` and `b` in characters.
+pub(crate) fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut curr = vec![0; b_chars.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, &cb) in b_chars.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b_chars.len()]
+}
+
+/// Returns up to three candidates within edit distance 2 of `key`, closest
+/// first.
+pub(crate) fn suggest_similar<'a>(
+    key: &str,
+    candidates: &'a HashSet<String>,
+) -> Vec<(&'a String, usize)> {
+    let key_len = key.chars().count();
+    let mut suggestions: Vec<_> = candidates
+        .iter()
+        .filter(|candidate| candidate.chars().count().abs_diff(key_len) <= MAX_SUGGESTION_DISTANCE)
+        .map(|candidate| (candidate, levenshtein_distance(key, candidate)))
+        .filter(|&(_, distance)| distance <= MAX_SUGGESTION_DISTANCE)
+        .collect();
+    suggestions.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// Returns the keys whose non-empty string values are identical in both
+/// locales, which may be untranslated copies.
+pub(crate) fn detect_identical_values(
+    left: &Map<String, Value>,
+    right: &Map<String, Value>,
+) -> Vec<String> {
+    let mut keys: Vec<_> = left
+        .iter()
+        .filter(|(key, value)| {
+            value.as_str().is_some_and(|s| !s.is_empty()) && right.get(*key) == Some(*value)
+        })
+        .map(|(key, _)| key.clone())
+        .collect();
+    keys.sort();
+    keys
+}
+
+pub(crate) fn print_identical_values(left_name: &str, right_name: &str, keys: &[String]) {
+    if keys.is_empty() {
+        println!("No keys have identical values in `{left_name}` and `{right_name}`.");
+        println!();
+        return;
+    }
+    println!(
+        "⚠️ Keys with identical values in `{left_name}` and `{right_name}` (possibly untranslated):"
+    );
+    for key in keys {
+        println!("  - {key}");
+    }
+    println!(
+        "These may be intentional, e.g. proper nouns or brand names; suppress them with \
+         `--ignore-identical-values <KEY>`."
+    );
+    println!();
+}
+
+/// Returns the format placeholders in a locale value, e.g. `{}` and `{name}`
+/// in `"{} selected in {name}"`. Escaped braces such as `{{` are not
+/// placeholders.
+pub(crate) fn extract_placeholders(value: &str) -> BTreeSet<String> {
+    PLACEHOLDER_RE
+        .find_iter(&value.replace("{{", "").replace("}}", ""))
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+/// A key whose placeholders differ between two locales.
+pub(crate) struct PlaceholderMismatch {
+    pub(crate) key: String,
+    pub(crate) left: BTreeSet<String>,
+    pub(crate) right: BTreeSet<String>,
+}
+
+/// Returns the keys whose non-empty values in both locales have different
+/// placeholders, which would break formatting of the translated text.
+pub(crate) fn detect_placeholder_mismatches(
+    left: &Map<String, Value>,
+    right: &Map<String, Value>,
+) -> Vec<PlaceholderMismatch> {
+    let mut mismatches: Vec<_> = left
+        .iter()
+        .filter_map(|(key, value)| {
+            let left_value = value.as_str().filter(|s| !s.is_empty())?;
+            let right_value = right.get(key)?.as_str().filter(|s| !s.is_empty())?;
+            let left = extract_placeholders(left_value);
+            let right = extract_placeholders(right_value);
+            (left != right).then(|| PlaceholderMismatch {
+                key: key.clone(),
+                left,
+                right,
+            })
+        })
+        .collect();
+    mismatches.sort_by(|a, b| a.key.cmp(&b.key));
+    mismatches
+}
+
+pub(crate) fn print_placeholder_mismatches(
+    left_name: &str,
+    right_name: &str,
+    mismatches: &[PlaceholderMismatch],
+) {
+    if mismatches.is_empty() {
+        return;
+    }
+    println!("❌ Keys whose placeholders differ between `{left_name}` and `{right_name}`:");
+    for mismatch in mismatches {
+        println!(
+            "  - {}: {} / {}",
+            mismatch.key,
+            join_placeholders(&mismatch.left),
+            join_placeholders(&mismatch.right)
+        );
+    }
+    println!();
+}
+
+/// A key whose array value, e.g. plural forms, has entries with different
+/// placeholders.
+pub(crate) struct ArrayPlaceholderMismatch {
+    pub(crate) key: String,
+    /// The placeholders of each non-empty string entry, in order.
+    pub(crate) entries: Vec<BTreeSet<String>>,
+}
+
+/// Checks that the non-empty string entries of the array value of `key`, e.g.
+/// `["{n} item", "{n} items"]`, all have the same placeholders.
+pub(crate) fn validate_array_values(
+    key: &str,
+    values: &[Value],
+) -> Option<ArrayPlaceholderMismatch> {
+    let entries: Vec<_> = values
+        .iter()
+        .filter_map(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .map(extract_placeholders)
+        .collect();
+    entries
+        .windows(2)
+        .any(|pair| pair[0] != pair[1])
+        .then(|| ArrayPlaceholderMismatch {
+            key: key.to_string(),
+            entries,
+        })
+}
+
+/// Returns the keys of `entries` whose array values fail
+/// [`validate_array_values`].
+pub(crate) fn detect_array_placeholder_mismatches(
+    entries: &Map<String, Value>,
+) -> Vec<ArrayPlaceholderMismatch> {
+    let mut mismatches: Vec<_> = entries
+        .iter()
+        .filter_map(|(key, value)| validate_array_values(key, value.as_array()?))
+        .collect();
+    mismatches.sort_by(|a, b| a.key.cmp(&b.key));
+    mismatches
+}
+
+pub(crate) fn print_array_placeholder_mismatches(
+    name: &str,
+    mismatches: &[ArrayPlaceholderMismatch],
+) {
+    if mismatches.is_empty() {
+        return;
+    }
+    println!("❌ Array values in `{name}` whose entries have different placeholders:");
+    for mismatch in mismatches {
+        let entries: Vec<_> = mismatch.entries.iter().map(join_placeholders).collect();
+        println!("  - {}: {}", mismatch.key, entries.join(" / "));
+    }
+    println!();
+}
+
+fn join_placeholders(placeholders: &BTreeSet<String>) -> String {
+    if placeholders.is_empty() {
+        "(none)".to_string()
+    } else {
+        placeholders.iter().cloned().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Returns the keys in the locale file at `path` whose value is an empty
+/// string, i.e. entries that have not been translated yet.
+pub(crate) fn detect_empty_values(
+    path: &Path,
+    format: LocaleFormat,
+    options: ReadOptions,
+) -> Result<Vec<String>, io::Error> {
+    let mut keys: Vec<_> = format
+        .read(path, options)?
+        .into_iter()
+        .filter(|(_, value)| value.as_str().is_some_and(str::is_empty))
+        .map(|(key, _)| key)
+        .collect();
+    keys.sort();
+    Ok(keys)
+}
+
+pub(crate) fn print_empty_values(locales: &[(String, Vec<String>)]) {
+    println!("=== Empty Translations ===");
+    for (name, keys) in locales {
+        if keys.is_empty() {
+            println!("No empty values in `{name}`.");
+            continue;
+        }
+        println!("⚠️ Keys in `{name}` with empty values:");
+        for key in keys {
+            println!("  - {key}");
+        }
+    }
+    println!();
+}
@@ -0,0 +1,256 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use toml::Value as TomlValue;
+
+const DEFAULT_EXCLUDED_DIRS: &[&str] = &["src/bin"];
+const DEFAULT_EXCLUDED_PREFIXES: &[&str] = &["report-"];
+const DEFAULT_HTML_I18N_ATTRIBUTES: &[&str] =
+    &["data-i18n", "data-i18n-key", "data-translate", "data-t"];
+const DEFAULT_TRANSLATION_HOOK_NAMES: &[&str] = &["use_translation", "use_i18n"];
+
+/// Lines before a string that extractors inspect by default.
+const DEFAULT_CONTEXT_LINES: usize = 4;
+
+/// Top-level keys recognized in the configuration file.
+const KNOWN_KEYS: &[&str] = &[
+    "excluded_dirs",
+    "excluded_prefixes",
+    "extractor",
+    "follow_symlinks",
+    "html_i18n_attributes",
+    "ignore_files",
+    "serde_rename_capitalized_as_key",
+    "strum_message_as_key",
+    "strum_serialize_as_key",
+    "translation_hook_names",
+];
+
+/// Extractor tables recognized under `[extractor]`, and their keys.
+const KNOWN_EXTRACTORS: &[&str] = &["frontary", "rust"];
+const KNOWN_EXTRACTOR_KEYS: &[&str] = &["context_lines"];
+
+/// Settings of one extractor, read from an `[extractor.<name>]` table.
+pub(crate) struct ExtractorConfig {
+    /// Number of lines before a string that are checked for the macro or
+    /// statement it belongs to.
+    pub(crate) context_lines: usize,
+}
+
+impl Default for ExtractorConfig {
+    fn default() -> Self {
+        Self {
+            context_lines: DEFAULT_CONTEXT_LINES,
+        }
+    }
+}
+
+/// Settings loaded from the TOML configuration file passed via `--config`.
+#[allow(clippy::struct_excessive_bools)] // each bool is an independent option
+pub(crate) struct Config {
+    /// Directories skipped during source traversal, matched against the end of
+    /// each directory path (e.g. `src/bin` or `target`).
+    pub(crate) excluded_dirs: Vec<String>,
+    /// Prefixes of extracted strings that are never keys, e.g. `report-` for
+    /// report identifiers.
+    pub(crate) excluded_prefixes: Vec<String>,
+    /// HTML attributes whose values are translation keys, e.g. `data-i18n`.
+    pub(crate) html_i18n_attributes: Vec<String>,
+    /// Functions whose first string argument is a key, e.g. the
+    /// `use_translation` hook of Yew function components.
+    pub(crate) translation_hook_names: Vec<String>,
+    /// `.gitignore`-format files whose patterns, relative to each scanned
+    /// repository root, exclude source files.
+    pub(crate) ignore_files: Vec<PathBuf>,
+    /// Whether `#[strum(message = "...")]` and `detailed_message` values are
+    /// keys rather than excluded strings.
+    pub(crate) strum_message_as_key: bool,
+    /// Whether `#[strum(serialize = "...")]` values are keys rather than
+    /// excluded strings.
+    pub(crate) strum_serialize_as_key: bool,
+    /// Whether capitalized, multi-word `#[serde(rename = "...")]` values are
+    /// keys; all other rename values are always excluded.
+    pub(crate) serde_rename_capitalized_as_key: bool,
+    /// Whether source traversal follows symbolic links; when disabled,
+    /// symlinked files and directories are skipped.
+    pub(crate) follow_symlinks: bool,
+    /// Settings of the extractor for UI Rust sources (`[extractor.rust]`).
+    pub(crate) rust_extractor: ExtractorConfig,
+    /// Settings of the extractor for frontary sources
+    /// (`[extractor.frontary]`).
+    pub(crate) frontary_extractor: ExtractorConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            excluded_dirs: to_strings(DEFAULT_EXCLUDED_DIRS),
+            excluded_prefixes: to_strings(DEFAULT_EXCLUDED_PREFIXES),
+            html_i18n_attributes: to_strings(DEFAULT_HTML_I18N_ATTRIBUTES),
+            translation_hook_names: to_strings(DEFAULT_TRANSLATION_HOOK_NAMES),
+            ignore_files: Vec::new(),
+            strum_message_as_key: true,
+            strum_serialize_as_key: false,
+            serde_rename_capitalized_as_key: true,
+            follow_symlinks: true,
+            rust_extractor: ExtractorConfig::default(),
+            frontary_extractor: ExtractorConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    pub(crate) fn load(path: &Path) -> Result<Self, io::Error> {
+        Self::from_value(&read_toml(path)?)
+    }
+
+    /// Builds the configuration from a parsed file, failing on values of the
+    /// wrong type.
+    pub(crate) fn from_value(value: &TomlValue) -> Result<Self, io::Error> {
+        let mut config = Self::default();
+        if let Some(dirs) = string_list(value, "excluded_dirs")? {
+            config.excluded_dirs = dirs;
+        }
+        if let Some(prefixes) = string_list(value, "excluded_prefixes")? {
+            config.excluded_prefixes = prefixes;
+        }
+        if let Some(attributes) = string_list(value, "html_i18n_attributes")? {
+            config.html_i18n_attributes = attributes;
+        }
+        if let Some(names) = string_list(value, "translation_hook_names")? {
+            config.translation_hook_names = names;
+        }
+        if let Some(files) = string_list(value, "ignore_files")? {
+            config.ignore_files = files.into_iter().map(PathBuf::from).collect();
+        }
+        if let Some(enabled) = boolean(value, "strum_message_as_key")? {
+            config.strum_message_as_key = enabled;
+        }
+        if let Some(enabled) = boolean(value, "strum_serialize_as_key")? {
+            config.strum_serialize_as_key = enabled;
+        }
+        if let Some(enabled) = boolean(value, "serde_rename_capitalized_as_key")? {
+            config.serde_rename_capitalized_as_key = enabled;
+        }
+        if let Some(enabled) = boolean(value, "follow_symlinks")? {
+            config.follow_symlinks = enabled;
+        }
+        if let Some(extractor) = extractor(value, "rust")? {
+            config.rust_extractor = extractor;
+        }
+        if let Some(extractor) = extractor(value, "frontary")? {
+            config.frontary_extractor = extractor;
+        }
+        Ok(config)
+    }
+}
+
+pub(crate) fn read_toml(path: &Path) -> Result<TomlValue, io::Error> {
+    let toml_str = fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("Failed to read {}: {e}", path.display())))?;
+    toml::from_str(&toml_str)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid TOML: {e}")))
+}
+
+/// Returns the keys of `value` that the configuration does not recognize,
+/// which are most likely typos. Keys in `[extractor]` tables are returned
+/// with their dotted path, e.g. `extractor.rust.context_line`.
+pub(crate) fn unknown_keys(value: &TomlValue) -> Vec<String> {
+    let mut unknown = table_unknown_keys(value, KNOWN_KEYS, "");
+    if let Some(extractors) = value.get("extractor") {
+        unknown.extend(table_unknown_keys(
+            extractors,
+            KNOWN_EXTRACTORS,
+            "extractor.",
+        ));
+        for name in KNOWN_EXTRACTORS {
+            if let Some(extractor) = extractors.get(name) {
+                unknown.extend(table_unknown_keys(
+                    extractor,
+                    KNOWN_EXTRACTOR_KEYS,
+                    &format!("extractor.{name}."),
+                ));
+            }
+        }
+    }
+    unknown
+}
+
+fn table_unknown_keys(value: &TomlValue, known: &[&str], prefix: &str) -> Vec<String> {
+    value
+        .as_table()
+        .map(|table| {
+            table
+                .keys()
+                .filter(|key| !known.contains(&key.as_str()))
+                .map(|key| format!("{prefix}{key}"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(ToString::to_string).collect()
+}
+
+/// Reads `key` as an array of strings, returning `None` if it is absent.
+fn string_list(value: &TomlValue, key: &str) -> Result<Option<Vec<String>>, io::Error> {
+    let Some(entry) = value.get(key) else {
+        return Ok(None);
+    };
+    entry
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .map(|item| item.as_str().map(ToString::to_string))
+                .collect::<Option<Vec<_>>>()
+        })
+        .map(Some)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("`{key}` must be an array of strings"),
+            )
+        })
+}
+
+/// Reads `key` as a boolean, returning `None` if it is absent.
+fn boolean(value: &TomlValue, key: &str) -> Result<Option<bool>, io::Error> {
+    value
+        .get(key)
+        .map(|entry| {
+            entry.as_bool().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("`{key}` must be a boolean"),
+                )
+            })
+        })
+        .transpose()
+}
+
+/// Reads the `[extractor.<name>]` table, returning `None` if it is absent.
+fn extractor(value: &TomlValue, name: &str) -> Result<Option<ExtractorConfig>, io::Error> {
+    let Some(table) = value
+        .get("extractor")
+        .and_then(|extractors| extractors.get(name))
+    else {
+        return Ok(None);
+    };
+    let mut extractor = ExtractorConfig::default();
+    if let Some(entry) = table.get("context_lines") {
+        extractor.context_lines = entry
+            .as_integer()
+            .and_then(|lines| usize::try_from(lines).ok())
+            .filter(|&lines| lines > 0)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("`extractor.{name}.context_lines` must be a positive integer"),
+                )
+            })?;
+    }
+    Ok(Some(extractor))
+}
@@ -0,0 +1,220 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::locale::{
+    JsonLocaleReader, LocaleEncoding, LocaleFormat, LocaleReader, ReadOptions, locale_files_in_dir,
+};
+
+/// Writes one row per key with its value in each locale as UTF-8 CSV with a
+/// byte order mark, so spreadsheet applications detect the encoding.
+pub(crate) fn write_csv(
+    path: &Path,
+    keys: &HashSet<String>,
+    locales: &[(&str, &Map<String, Value>)],
+) -> Result<(), io::Error> {
+    let mut keys: Vec<_> = keys.iter().collect();
+    keys.sort();
+    let mut content = String::from("\u{feff}");
+    let header = std::iter::once("key").chain(locales.iter().map(|(name, _)| *name));
+    push_csv_row(&mut content, header.map(Into::into));
+    for key in &keys {
+        let values = locales.iter().map(|(_, entries)| match entries.get(*key) {
+            Some(Value::String(value)) => value.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(value) => value.to_string(),
+        });
+        push_csv_row(&mut content, std::iter::once((*key).clone()).chain(values));
+    }
+    fs::write(path, content)
+        .map_err(|e| Error::new(e.kind(), format!("Failed to write {}: {e}", path.display())))?;
+    eprintln!("💾 Exported {} keys to {}", keys.len(), path.display());
+    Ok(())
+}
+
+/// Appends an RFC 4180 record, quoting fields that contain a comma, quote, or
+/// line break.
+fn push_csv_row(content: &mut String, fields: impl Iterator<Item = String>) {
+    for (index, field) in fields.enumerate() {
+        if index > 0 {
+            content.push(',');
+        }
+        if field.contains([',', '"', '\r', '\n']) {
+            content.push('"');
+            content.push_str(&field.replace('"', "\"\""));
+            content.push('"');
+        } else {
+            content.push_str(&field);
+        }
+    }
+    content.push_str("\r\n");
+}
+
+/// Splits RFC 4180 CSV into records, handling quoted fields that contain
+/// commas, doubled quotes, or line breaks.
+fn parse_csv(content: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Counts of the changes `import_csv` made to one locale file.
+#[derive(Default)]
+struct ImportSummary {
+    added: usize,
+    updated: usize,
+    skipped: usize,
+}
+
+/// Merges the values in a CSV written by `--export-csv` into the JSON locale
+/// files in `locale_dir`. Existing keys are only changed when `overwrite` is
+/// set, empty cells are skipped, and non-string values such as arrays are
+/// never replaced. Files without changes are left untouched.
+pub(crate) fn import_csv(
+    path: &Path,
+    locale_dir: &Path,
+    overwrite: bool,
+    options: ReadOptions,
+) -> Result<(), io::Error> {
+    if !matches!(options.encoding, LocaleEncoding::Utf8) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "import-csv writes UTF-8 and cannot rewrite {} locale files",
+                options.encoding.name()
+            ),
+        ));
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| Error::new(e.kind(), format!("Failed to read {}: {e}", path.display())))?;
+    let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+    let records = parse_csv(content).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("CSV error in {}: {e}", path.display()),
+        )
+    })?;
+    let Some((header, rows)) = records.split_first() else {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("{} is empty", path.display()),
+        ));
+    };
+    let available: Vec<_> = locale_files_in_dir(locale_dir, LocaleFormat::Json)?
+        .iter()
+        .filter_map(|path| path.file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .collect();
+    let locales = &header[1..];
+    if header.first().map(String::as_str) != Some("key")
+        || locales.is_empty()
+        || locales.iter().any(|name| !available.contains(name))
+        || locales.iter().collect::<HashSet<_>>().len() != locales.len()
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unexpected CSV header `{}`; expected `key` followed by locale columns from {}",
+                header.join(","),
+                available.join(", ")
+            ),
+        ));
+    }
+
+    for (column, locale) in locales.iter().enumerate() {
+        let locale_path = locale_dir.join(format!("{locale}.json"));
+        let mut entries = JsonLocaleReader.read(&locale_path, options)?;
+        let mut summary = ImportSummary::default();
+        for row in rows {
+            let (Some(key), Some(value)) = (row.first(), row.get(column + 1)) else {
+                continue;
+            };
+            if key.is_empty() || value.is_empty() {
+                summary.skipped += 1;
+                continue;
+            }
+            match entries.get(key) {
+                None => summary.added += 1,
+                Some(Value::String(current)) if current == value => continue,
+                // exported as JSON text, which a string cannot replace
+                Some(current)
+                    if !current.is_string()
+                        && serde_json::from_str::<Value>(value)
+                            .is_ok_and(|cell| cell == *current) =>
+                {
+                    continue;
+                }
+                Some(Value::String(_)) if overwrite => summary.updated += 1,
+                Some(_) => {
+                    summary.skipped += 1;
+                    continue;
+                }
+            }
+            entries.insert(key.clone(), Value::String(value.clone()));
+        }
+        if summary.added + summary.updated > 0 {
+            write_locale_json(&locale_path, &entries)?;
+        }
+        println!(
+            "{}: {} added, {} updated, {} skipped",
+            locale_path.display(),
+            summary.added,
+            summary.updated,
+            summary.skipped
+        );
+    }
+    Ok(())
+}
+
+/// Writes `entries` in their original order, keeping the UTF-8 byte order mark
+/// of a file that had one.
+fn write_locale_json(path: &Path, entries: &Map<String, Value>) -> Result<(), io::Error> {
+    let bom = if fs::read(path)
+        .map_err(|e| Error::new(e.kind(), format!("Failed to read {}: {e}", path.display())))?
+        .starts_with("\u{feff}".as_bytes())
+    {
+        "\u{feff}"
+    } else {
+        ""
+    };
+    let content = serde_json::to_string_pretty(entries)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("JSON error: {e}")))?;
+    fs::write(path, format!("{bom}{content}\n"))
+        .map_err(|e| Error::new(e.kind(), format!("Failed to write {}: {e}", path.display())))
+}
@@ -0,0 +1,1292 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Macros whose string arguments are frontary translation keys.
+const TEXT_MACROS: &[&str] = &["text!(", "get_text!("];
+
+/// Number of lines before a string searched for an enclosing text macro.
+const MACRO_SCAN_LINES: usize = 10;
+
+/// Number of lines before a string searched for the `get_text!(` whose key
+/// argument it may be.
+const GET_TEXT_SCAN_LINES: usize = 6;
+
+/// Number of bytes before a string searched for a `format!(` whose template
+/// it is.
+const FORMAT_MACRO_SCAN_BYTES: usize = 50;
+
+/// Matches the header of a `Display` implementation, with or without the
+/// `fmt::` or `std::fmt::` path and generic parameters.
+static DISPLAY_IMPL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bimpl\s*(?:<[^>{]*>)?\s*(?:std::)?(?:fmt::)?Display\s+for\b")
+        .expect("valid regex")
+});
+
+/// Matches a string literal at the start of the input.
+static LEADING_STRING_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^"((?:[^"\\]|\\.)*)""#).expect("valid regex"));
+
+/// Matches a `write!`, `writeln!`, `eprint!`, or `eprintln!` invocation, whose
+/// strings are formatted output rather than UI text.
+static WRITE_MACRO_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:write|eprint)(?:ln)?!\s*\(").expect("valid regex"));
+
+/// Matches `t("key")`, `i18n("key")`, and `translate("key")` calls in
+/// TypeScript, capturing a double- or single-quoted key.
+static TYPESCRIPT_KEY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b(?:t|i18n|translate)\(\s*(?:"([^"\\]*)"|'([^'\\]*)')"#).expect("valid regex")
+});
+
+/// Matches the opening of a Leptos `view! { ... }` macro.
+static LEPTOS_VIEW_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bview!\s*\{").expect("valid regex"));
+
+/// Matches a call whose string argument is a fallback value displayed in
+/// place of a missing one, ending right before the argument's opening quote.
+static FALLBACK_CALL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\.(?:unwrap_or\(|unwrap_or_else\(\s*\|\|\s*|map_or\()\s*$").expect("valid regex")
+});
+
+/// Matches a Yew `html!` prop rendered as user-visible text, ending right
+/// before the value's opening quote, e.g. `aria_label={` or `placeholder=`.
+static TEXT_PROP_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:aria_label|title|placeholder|alt)\s*=\s*\{?\s*$").expect("valid regex")
+});
+
+/// Matches lowercase words joined by underscores, the shape of lint names such
+/// as `dead_code` or `clippy::too_many_lines`.
+static LINT_NAME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:[a-z]+::)?[a-z][a-z0-9]*(?:_[a-z0-9]+)+$").expect("valid regex")
+});
+
+/// Matches lowercase alphanumeric words joined by `_` or `-` without spaces,
+/// the shape of feature flags such as `serde` or `unstable-api`.
+static FEATURE_FLAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z0-9]+(?:[-_][a-z0-9]+)*$").expect("valid regex"));
+
+/// Matches strings made up only of HTML entities such as `&nbsp;` or `&#160;`,
+/// optionally separated by whitespace.
+static HTML_ENTITY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:\s*&(?:[a-zA-Z]+|#\d+|#[xX][0-9a-fA-F]+);)+\s*$").expect("valid regex")
+});
+
+/// Matches hex colors and CSS color functions such as `#ff0000` or
+/// `rgba(255, 0, 0, 0.5)`.
+static CSS_COLOR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:#[0-9a-fA-F]{3,8}$|(?i:rgba?|hsla?)\()").expect("valid regex")
+});
+
+/// CSS named colors and the `transparent` and `currentcolor` keywords.
+const CSS_COLOR_NAMES: [&str; 151] = [
+    "aliceblue",
+    "antiquewhite",
+    "aqua",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanchedalmond",
+    "blue",
+    "blueviolet",
+    "brown",
+    "burlywood",
+    "cadetblue",
+    "chartreuse",
+    "chocolate",
+    "coral",
+    "cornflowerblue",
+    "cornsilk",
+    "crimson",
+    "currentColor",
+    "currentcolor",
+    "cyan",
+    "darkblue",
+    "darkcyan",
+    "darkgoldenrod",
+    "darkgray",
+    "darkgreen",
+    "darkgrey",
+    "darkkhaki",
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkslategrey",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dimgrey",
+    "dodgerblue",
+    "firebrick",
+    "floralwhite",
+    "forestgreen",
+    "fuchsia",
+    "gainsboro",
+    "ghostwhite",
+    "gold",
+    "goldenrod",
+    "gray",
+    "green",
+    "greenyellow",
+    "grey",
+    "honeydew",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lavender",
+    "lavenderblush",
+    "lawngreen",
+    "lemonchiffon",
+    "lightblue",
+    "lightcoral",
+    "lightcyan",
+    "lightgoldenrodyellow",
+    "lightgray",
+    "lightgreen",
+    "lightgrey",
+    "lightpink",
+    "lightsalmon",
+    "lightseagreen",
+    "lightskyblue",
+    "lightslategray",
+    "lightslategrey",
+    "lightsteelblue",
+    "lightyellow",
+    "lime",
+    "limegreen",
+    "linen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "mintcream",
+    "mistyrose",
+    "moccasin",
+    "navajowhite",
+    "navy",
+    "oldlace",
+    "olive",
+    "olivedrab",
+    "orange",
+    "orangered",
+    "orchid",
+    "palegoldenrod",
+    "palegreen",
+    "paleturquoise",
+    "palevioletred",
+    "papayawhip",
+    "peachpuff",
+    "peru",
+    "pink",
+    "plum",
+    "powderblue",
+    "purple",
+    "rebeccapurple",
+    "red",
+    "rosybrown",
+    "royalblue",
+    "saddlebrown",
+    "salmon",
+    "sandybrown",
+    "seagreen",
+    "seashell",
+    "sienna",
+    "silver",
+    "skyblue",
+    "slateblue",
+    "slategray",
+    "slategrey",
+    "snow",
+    "springgreen",
+    "steelblue",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "transparent",
+    "turquoise",
+    "violet",
+    "wheat",
+    "white",
+    "whitesmoke",
+    "yellow",
+    "yellowgreen",
+];
+
+/// Matches CSS lengths and percentages such as `100px`, `1.5em`, or `50%`.
+static CSS_MEASUREMENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\d+(\.\d+)?(px|em|rem|vh|vw|vmin|vmax|pt|pc|cm|mm|in|ex|ch|%|fr)$")
+        .expect("valid regex")
+});
+
+/// CSS keywords that are valid for most properties.
+const CSS_KEYWORDS: [&str; 6] = ["auto", "inherit", "initial", "none", "normal", "unset"];
+
+/// Matches a `concat!` call whose arguments are all string literals.
+static CONCAT_MACRO_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"concat!\(\s*((?:"(?:[^"\\]|\\.)*"\s*,\s*)*"(?:[^"\\]|\\.)*")\s*,?\s*\)"#)
+        .expect("valid regex")
+});
+
+/// Matches a single string literal, capturing its contents.
+static STRING_LITERAL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""((?:[^"\\]|\\.)*)""#).expect("valid regex"));
+
+/// `target_os` values, which appear in `cfg` conditions rather than the UI.
+const TARGET_OS_NAMES: [&str; 5] = ["android", "ios", "linux", "macos", "windows"];
+
+/// Matches the string literal a `match` arm evaluates to.
+static MATCH_ARM_STRING_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"=>\s*"((?:[^"\\]|\\.)*)""#).expect("valid regex"));
+
+/// Matches an identifier, such as a variable passed as a macro argument.
+static IDENTIFIER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").expect("valid regex"));
+
+/// Matches the header of an `Error` implementation, with or without the
+/// `error::` or `std::error::` path and generic parameters.
+static ERROR_IMPL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bimpl\s*(?:<[^>{]*>)?\s*(?:std::)?(?:error::)?Error\s+for\b")
+        .expect("valid regex")
+});
+
+/// Why a string literal was not treated as a translation key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) enum FilterReason {
+    NoAlphabetic,
+    PathOrSelector,
+    DateFormat,
+    Cjk,
+    ExcludedPrefix,
+    SingleCharacter,
+    LineContext,
+    PrecedingContext,
+    FixedExclusion,
+    CssSelector,
+    DisplayImpl,
+    ErrorImpl,
+    ExcludeRegex,
+    TooLong,
+    TooFewWords,
+    LintAttribute,
+    LintName,
+    CfgAttribute,
+    FeatureFlagLike,
+    HtmlEntity,
+    CssColor,
+    CssMeasurement,
+    CfgMacro,
+    TargetOs,
+    LowAlphabeticRatio,
+}
+
+impl fmt::Display for FilterReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Self::NoAlphabetic => "no alphabetic characters",
+            Self::PathOrSelector => "starts with `/` or `#`",
+            Self::DateFormat => "date format string",
+            Self::Cjk => "contains Korean or Chinese characters",
+            Self::ExcludedPrefix => "starts with an excluded prefix",
+            Self::SingleCharacter => "single character",
+            Self::LineContext => "excluded by the current line",
+            Self::PrecedingContext => "excluded by the preceding lines",
+            Self::FixedExclusion => "listed in FIXED_EXCLUDED_STRINGS",
+            Self::CssSelector => "CSS class or id",
+            Self::DisplayImpl => "inside an `impl Display` block",
+            Self::ErrorImpl => "inside an `impl Error` block",
+            Self::ExcludeRegex => "matches an `--exclude-regex` pattern",
+            Self::TooLong => "longer than `--max-key-length`",
+            Self::TooFewWords => "fewer words than `--min-word-count`",
+            Self::LintAttribute => "inside a lint attribute",
+            Self::LintName => "looks like a lint name",
+            Self::CfgAttribute => "inside a `#[cfg(...)]` attribute",
+            Self::FeatureFlagLike => "looks like a feature flag name",
+            Self::HtmlEntity => "HTML entity",
+            Self::CssColor => "CSS color value",
+            Self::CssMeasurement => "CSS measurement or keyword",
+            Self::CfgMacro => "inside or after a `cfg!(...)` condition",
+            Self::TargetOs => "target OS name",
+            Self::LowAlphabeticRatio => "fewer letters than `--min-alphabetic-ratio`",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// Runtime settings that adjust how `collect_strings_from_file` filters
+/// strings.
+#[allow(clippy::struct_excessive_bools)] // each bool is an independent option
+pub(crate) struct ExtractOptions {
+    /// Strings matching any of these patterns are excluded.
+    pub(crate) exclude_patterns: Vec<Regex>,
+    /// Strings matching any of these patterns bypass every filter.
+    pub(crate) include_patterns: Vec<Regex>,
+    /// Strings starting with any of these prefixes are excluded.
+    pub(crate) excluded_prefixes: Vec<String>,
+    /// Functions whose first string argument is always kept as a key, e.g.
+    /// `use_translation("Key")`.
+    pub(crate) translation_hooks: Vec<String>,
+    /// Strings with more characters than this are excluded.
+    pub(crate) max_key_length: Option<usize>,
+    /// Strings with fewer whitespace-separated words than this are excluded.
+    pub(crate) min_word_count: usize,
+    /// Strings whose share of alphabetic characters, ignoring whitespace, is
+    /// below this are excluded.
+    pub(crate) min_alphabetic_ratio: f64,
+    /// Whether strum `message`/`detailed_message` values are kept as keys;
+    /// when `false` they are excluded.
+    pub(crate) strum_message_as_key: bool,
+    /// Whether strum `serialize` values are kept as keys; when `false` they
+    /// are excluded.
+    pub(crate) strum_serialize_as_key: bool,
+    /// Whether capitalized, multi-word serde rename values are kept as keys.
+    pub(crate) serde_rename_capitalized_as_key: bool,
+    /// Number of lines before a string checked for the statement it is in.
+    pub(crate) context_lines: usize,
+    /// Whether element text in Leptos `view!` macros, e.g. `<p>"Key"</p>`, is
+    /// kept as a key.
+    pub(crate) leptos_mode: bool,
+    /// Whether strings containing Korean or Chinese characters are kept.
+    pub(crate) allow_cjk: bool,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self {
+            exclude_patterns: Vec::new(),
+            include_patterns: Vec::new(),
+            excluded_prefixes: vec!["report-".to_string()],
+            translation_hooks: vec!["use_translation".to_string(), "use_i18n".to_string()],
+            max_key_length: None,
+            min_word_count: 1,
+            min_alphabetic_ratio: 0.5,
+            strum_message_as_key: true,
+            strum_serialize_as_key: false,
+            serde_rename_capitalized_as_key: true,
+            context_lines: 4,
+            leptos_mode: false,
+            allow_cjk: false,
+        }
+    }
+}
+
+impl ExtractOptions {
+    pub(crate) fn is_included(&self, string: &str) -> bool {
+        self.include_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(string))
+    }
+}
+
+/// Strings found in a source file, split into candidate keys and strings
+/// that were filtered out.
+#[derive(Default)]
+pub(crate) struct Extraction {
+    pub(crate) kept: HashSet<String>,
+    pub(crate) filtered: HashMap<String, FilterReason>,
+}
+
+impl Extraction {
+    pub(crate) fn merge(&mut self, other: Self) {
+        self.kept.extend(other.kept);
+        for (string, reason) in other.filtered {
+            self.filtered.entry(string).or_insert(reason);
+        }
+    }
+}
+
+pub(crate) fn collect_strings_from_file(
+    path: &Path,
+    re: &Regex,
+    options: &ExtractOptions,
+) -> Result<Extraction, io::Error> {
+    let content = resolve_concat_macros(&fs::read_to_string(path)?);
+
+    let mut impl_blocks = find_impl_blocks(&content, &DISPLAY_IMPL_RE, FilterReason::DisplayImpl);
+    impl_blocks.extend(find_impl_blocks(
+        &content,
+        &ERROR_IMPL_RE,
+        FilterReason::ErrorImpl,
+    ));
+
+    let mut extraction = Extraction::default();
+    for m in re.captures_iter(&content).filter_map(|cap| cap.get(1)) {
+        // surrounding whitespace is padding, not part of the key
+        let matched_string = m.as_str().trim();
+        match filter_reason(
+            &content,
+            m.start() - 1,
+            matched_string,
+            &impl_blocks,
+            options,
+        ) {
+            Some(reason) => {
+                extraction
+                    .filtered
+                    .entry(matched_string.to_string())
+                    .or_insert(reason);
+            }
+            None => {
+                extraction.kept.insert(matched_string.to_string());
+            }
+        }
+    }
+
+    Ok(extraction)
+}
+
+/// Replaces `concat!("a", "b", ...)` calls with the single literal `"ab"` so
+/// the composite string is extracted instead of its parts.
+fn resolve_concat_macros(content: &str) -> String {
+    CONCAT_MACRO_RE
+        .replace_all(content, |caps: &regex::Captures| {
+            let joined: String = STRING_LITERAL_RE
+                .captures_iter(&caps[1])
+                .map(|literal| literal[1].to_string())
+                .collect();
+            format!("\"{joined}\"")
+        })
+        .into_owned()
+}
+
+/// Returns whether `s` starts with an uppercase letter, contains a space, and
+/// is not entirely uppercase, like `"Content Type"`.
+fn is_capitalized_label(s: &str) -> bool {
+    s.chars().next().is_some_and(char::is_uppercase)
+        && s.contains(' ')
+        && s.chars().any(char::is_lowercase)
+}
+
+/// Returns whether `c` is a Hangul syllable or a CJK unified ideograph, i.e.
+/// text that is already translated rather than an English key.
+fn is_cjk(c: char) -> bool {
+    ('\u{AC00}'..='\u{D7A3}').contains(&c) || ('\u{4E00}'..='\u{9FFF}').contains(&c)
+}
+
+/// Returns the share of the non-whitespace characters of `s` that are
+/// alphabetic, or 0 if there are none.
+#[allow(clippy::cast_precision_loss)] // string lengths are far below 2^52
+fn alphabetic_ratio(s: &str) -> f64 {
+    let (alphabetic, total) = s
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .fold((0_usize, 0_usize), |(alphabetic, total), c| {
+            (alphabetic + usize::from(c.is_alphabetic()), total + 1)
+        });
+    if total == 0 {
+        return 0.0;
+    }
+    alphabetic as f64 / total as f64
+}
+
+/// Counts the whitespace-separated, non-empty segments of `s`.
+fn word_count(s: &str) -> usize {
+    s.split_whitespace().count()
+}
+
+/// An `impl` block whose string literals are never keys.
+struct ImplBlock {
+    range: Range<usize>,
+    reason: FilterReason,
+}
+
+/// Finds the byte ranges of the blocks whose header matches `header`.
+fn find_impl_blocks(content: &str, header: &Regex, reason: FilterReason) -> Vec<ImplBlock> {
+    header
+        .find_iter(content)
+        .filter_map(|m| {
+            let open = m.end() + content[m.end()..].find('{')?;
+            let close = find_matching_brace(content, open)?;
+            Some(ImplBlock {
+                range: open..close,
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// Returns the position of the `}` closing the `{` at `open`, skipping braces
+/// inside string literals such as `"{}"`.
+fn find_matching_brace(content: &str, open: usize) -> Option<usize> {
+    find_closing(content, open, '{', '}')
+}
+
+/// Returns the position of the `close` delimiter matching the `open_char` at
+/// `open`, skipping delimiters inside string and char literals.
+fn find_closing(content: &str, open: usize, open_char: char, close: char) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut skip_to = 0;
+    for (i, c) in content[open..].char_indices() {
+        if i < skip_to {
+            continue;
+        }
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '\'' => {
+                if let Some(len) = char_literal_len(&content[open + i..]) {
+                    skip_to = i + len;
+                }
+            }
+            '"' => in_string = true,
+            _ if c == open_char => depth += 1,
+            _ if c == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the byte length of the char literal, e.g. `'{'` or `'\''`, that
+/// `rest` starts with, or `None` if its `'` starts a lifetime or label.
+fn char_literal_len(rest: &str) -> Option<usize> {
+    let mut chars = rest.char_indices().skip(1);
+    let (_, first) = chars.next()?;
+    if first == '\\' {
+        // the escaped char, then up to `u{10FFFF}` before the closing quote
+        chars.next()?;
+        return chars
+            .take(9)
+            .find(|&(_, c)| c == '\'')
+            .map(|(end, _)| end + 1);
+    }
+    match chars.next()? {
+        (end, '\'') if first != '\'' && first != '\n' => Some(end + 1),
+        _ => None,
+    }
+}
+
+/// Returns the reason `matched_string`, whose opening quote is at `start`,
+/// is not a key, or `None` if it should be kept.
+fn filter_reason(
+    content: &str,
+    start: usize,
+    matched_string: &str,
+    impl_blocks: &[ImplBlock],
+    options: &ExtractOptions,
+) -> Option<FilterReason> {
+    if options.is_included(matched_string)
+        || is_translation_hook_argument(content, start, &options.translation_hooks)
+    {
+        return None;
+    }
+    if let Some(reason) = string_filter_reason(matched_string, options) {
+        return Some(reason);
+    }
+    if let Some(block) = impl_blocks
+        .iter()
+        .find(|block| block.range.contains(&start))
+    {
+        return Some(block.reason);
+    }
+    context_filter_reason(content, start, matched_string, options)
+}
+
+/// Checks `matched_string` on its own, regardless of where it appears.
+fn string_filter_reason(matched_string: &str, options: &ExtractOptions) -> Option<FilterReason> {
+    if matched_string.chars().all(|c| !c.is_alphabetic()) {
+        return Some(FilterReason::NoAlphabetic);
+    }
+    if alphabetic_ratio(matched_string) < options.min_alphabetic_ratio {
+        return Some(FilterReason::LowAlphabeticRatio);
+    }
+    if HTML_ENTITY_RE.is_match(matched_string) {
+        return Some(FilterReason::HtmlEntity);
+    }
+    if CSS_COLOR_RE.is_match(matched_string) || CSS_COLOR_NAMES.contains(&matched_string) {
+        return Some(FilterReason::CssColor);
+    }
+    if CSS_MEASUREMENT_RE.is_match(matched_string) || CSS_KEYWORDS.contains(&matched_string) {
+        return Some(FilterReason::CssMeasurement);
+    }
+    if matches!(matched_string.chars().next(), Some('/' | '#'))
+        && matched_string.chars().nth(1).is_some_and(|c| c != ' ')
+    {
+        return Some(FilterReason::PathOrSelector);
+    }
+    if matched_string.contains("%Y") {
+        return Some(FilterReason::DateFormat);
+    }
+    if !options.allow_cjk && matched_string.chars().any(is_cjk) {
+        return Some(FilterReason::Cjk);
+    }
+    if options
+        .excluded_prefixes
+        .iter()
+        .any(|prefix| matched_string.starts_with(prefix.as_str()))
+    {
+        return Some(FilterReason::ExcludedPrefix);
+    }
+    if matched_string.len() == 1 {
+        return Some(FilterReason::SingleCharacter);
+    }
+    if options
+        .max_key_length
+        .is_some_and(|max| matched_string.chars().count() > max)
+    {
+        return Some(FilterReason::TooLong);
+    }
+    if word_count(matched_string) < options.min_word_count {
+        return Some(FilterReason::TooFewWords);
+    }
+    if options
+        .exclude_patterns
+        .iter()
+        .any(|pattern| pattern.is_match(matched_string))
+    {
+        return Some(FilterReason::ExcludeRegex);
+    }
+    None
+}
+
+/// Returns whether the string whose opening quote is at `start` is the first
+/// argument of a call to one of `hooks` on the same line, e.g.
+/// `use_translation("Key")`.
+fn is_translation_hook_argument(content: &str, start: usize, hooks: &[String]) -> bool {
+    let line_start = content[..start].rfind('\n').map_or(0, |pos| pos + 1);
+    let Some(call) = content[line_start..start].trim_end().strip_suffix('(') else {
+        return false;
+    };
+    hooks.iter().any(|hook| {
+        call.strip_suffix(hook.as_str())
+            .is_some_and(|before| !before.ends_with(|c: char| c.is_alphanumeric() || c == '_'))
+    })
+}
+
+/// Returns whether `start` lies inside a `#[cfg(` attribute opened on the
+/// current line or one of the two lines before it.
+fn is_inside_cfg_attribute(content: &str, start: usize) -> bool {
+    let window_start = content[..start]
+        .rmatch_indices('\n')
+        .nth(2)
+        .map_or(0, |(i, _)| i + 1);
+    content[window_start..start]
+        .rfind("#[cfg(")
+        .is_some_and(|cfg| {
+            let attribute = &content[window_start + cfg..start];
+            attribute.matches('(').count() > attribute.matches(')').count()
+        })
+}
+
+/// Returns whether the string whose opening quote is at `start` is the text of
+/// an element, e.g. `<p>"Key"</p>`, in a Leptos `view!` macro opened on the
+/// current line or one of the `context_lines` lines before it.
+fn is_leptos_view_text(content: &str, start: usize, context_lines: usize) -> bool {
+    let window_start = content[..start]
+        .rmatch_indices('\n')
+        .nth(context_lines)
+        .map_or(0, |(i, _)| i + 1);
+    if !LEPTOS_VIEW_RE.is_match(&content[window_start..start]) {
+        return false;
+    }
+    let mut escaped = false;
+    let Some(close) = content[start + 1..].char_indices().find_map(|(i, c)| {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(start + 1 + i),
+            _ => {}
+        }
+        None
+    }) else {
+        return false;
+    };
+    content[..start].trim_end().ends_with('>')
+        && content[close + 1..].trim_start().starts_with("</")
+}
+
+/// Checks the source surrounding `matched_string`, whose opening quote is at
+/// `start`.
+fn context_filter_reason(
+    content: &str,
+    start: usize,
+    matched_string: &str,
+    options: &ExtractOptions,
+) -> Option<FilterReason> {
+    let line_start = content[..start].rfind('\n').map_or(0, |pos| pos + 1);
+    let line_end = content[start..]
+        .find('\n')
+        .map_or(content.len(), |pos| start + pos);
+    // `trim` also drops the `\r` that CRLF line endings leave before `line_end`
+    let current_line = content[line_start..line_end].trim();
+
+    let line_before = &content[line_start..start];
+    if FALLBACK_CALL_RE.is_match(line_before) {
+        return None;
+    }
+    if options.leptos_mode && is_leptos_view_text(content, start, options.context_lines) {
+        return None;
+    }
+    if current_line.contains("#[strum(message =") || current_line.contains("detailed_message =") {
+        return if options.strum_message_as_key {
+            None
+        } else {
+            Some(FilterReason::LineContext)
+        };
+    }
+    if current_line.contains("#[strum(serialize =") {
+        return if options.strum_serialize_as_key {
+            None
+        } else {
+            Some(FilterReason::LineContext)
+        };
+    }
+    // `rename = "Content Type"` is a label, `rename = "ftp brute force"` is not
+    if current_line.contains("#[serde(rename =") {
+        return if options.serde_rename_capitalized_as_key && is_capitalized_label(matched_string) {
+            None
+        } else {
+            Some(FilterReason::LineContext)
+        };
+    }
+    // only label-like prop values qualify; others are filtered as usual
+    if TEXT_PROP_RE.is_match(line_before) && matched_string.contains(' ') {
+        return None;
+    }
+
+    if is_inside_cfg_attribute(content, start) {
+        return Some(FilterReason::CfgAttribute);
+    }
+    if current_line.contains("cfg!(") {
+        return Some(FilterReason::CfgMacro);
+    }
+    if ["#[allow(", "#[deny(", "#[warn("]
+        .iter()
+        .any(|attribute| current_line.contains(attribute))
+    {
+        return Some(FilterReason::LintAttribute);
+    }
+
+    if current_line.contains("expect(")
+        || current_line.contains("Error::new(")
+        || current_line.contains("feature =")
+    {
+        return Some(FilterReason::LineContext);
+    }
+
+    let preceding_lines: Vec<&str> = content[..start]
+        .lines()
+        .rev()
+        .take(options.context_lines)
+        .map(str::trim)
+        .collect();
+
+    if preceding_lines
+        .first()
+        .is_some_and(|line| line.contains("text!("))
+    {
+        return None;
+    }
+
+    if preceding_lines.iter().any(|line| line.contains("if cfg!(")) {
+        return Some(FilterReason::CfgMacro);
+    }
+    // checked after the bypasses above, so `unwrap_or("item")` stays a key
+    if LINT_NAME_RE.is_match(matched_string) {
+        return Some(FilterReason::LintName);
+    }
+    if TARGET_OS_NAMES.contains(&matched_string) {
+        return Some(FilterReason::TargetOs);
+    }
+    if FEATURE_FLAG_RE.is_match(matched_string) {
+        return Some(FilterReason::FeatureFlagLike);
+    }
+
+    preceding_lines
+        .iter()
+        .enumerate()
+        .any(|(i, line)| {
+            line.contains("#[graphql(")
+                || (i == 0 && line.contains("type="))
+                || (i <= 1 && line.contains("anyhow!("))
+                || (i <= 2 && WRITE_MACRO_RE.is_match(line))
+                || (line.contains("format!(")
+                    && (i == 0
+                        || (i == 1 && preceding_lines.first().is_some_and(|prev| prev.is_empty()))
+                        || (i == 2 && preceding_lines.get(1).is_some_and(|prev| prev.is_empty()))))
+        })
+        .then_some(FilterReason::PrecedingContext)
+}
+
+/// Builds a regex capturing the values of the given HTML attributes.
+pub(crate) fn html_attribute_regex(attributes: &[String]) -> Result<Regex, io::Error> {
+    let names = attributes
+        .iter()
+        .map(|attribute| regex::escape(attribute))
+        .collect::<Vec<_>>()
+        .join("|");
+    Regex::new(&format!(
+        r#"(?:^|[\s<])(?:{names})\s*=\s*(?:"([^"]+)"|'([^']+)')"#
+    ))
+    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Collects translation keys from attributes such as `data-i18n="key"` in an
+/// HTML file.
+pub(crate) fn extract_keys_from_data_i18n(
+    path: &Path,
+    re: &Regex,
+) -> Result<HashSet<String>, io::Error> {
+    let content = fs::read_to_string(path)?;
+
+    Ok(re
+        .captures_iter(&content)
+        .filter_map(|cap| cap.get(1).or_else(|| cap.get(2)))
+        .map(|m| m.as_str().to_string())
+        .collect())
+}
+
+/// Collects translation keys passed to `t`, `i18n`, or `translate` in a
+/// TypeScript file.
+pub(crate) fn extract_keys_from_typescript(path: &Path) -> Result<HashSet<String>, io::Error> {
+    let content = fs::read_to_string(path)?;
+
+    Ok(TYPESCRIPT_KEY_RE
+        .captures_iter(&content)
+        .filter_map(|cap| cap.get(1).or_else(|| cap.get(2)))
+        .map(|m| m.as_str().to_string())
+        .collect())
+}
+
+pub(crate) fn extract_css_classes_and_ids(
+    css_file_paths: &[PathBuf],
+) -> Result<HashSet<String>, io::Error> {
+    let class_re = Regex::new(r"(?:[a-zA-Z]+\.)?\.([a-zA-Z][a-zA-Z0-9_-]*)")
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let id_re = Regex::new(r"(?:[a-zA-Z]+#)?#([a-zA-Z][a-zA-Z0-9_-]*)")
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let classes_and_ids = css_file_paths
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .flat_map(|line| {
+                    let mut combined_matches = Vec::new();
+
+                    combined_matches.extend(
+                        class_re
+                            .captures_iter(line)
+                            .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_owned())),
+                    );
+
+                    combined_matches.extend(
+                        id_re
+                            .captures_iter(line)
+                            .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_owned())),
+                    );
+
+                    combined_matches
+                })
+                .collect::<Vec<String>>()
+        })
+        .collect::<HashSet<String>>();
+
+    Ok(classes_and_ids)
+}
+
+/// Returns whether the string whose opening quote is at `quote` is the
+/// template of a `format!` call in the same expression.
+fn is_format_template(content: &str, quote: usize) -> bool {
+    let window =
+        &content[content.floor_char_boundary(quote.saturating_sub(FORMAT_MACRO_SCAN_BYTES))..quote];
+    window
+        .rfind("format!(")
+        .is_some_and(|pos| !window[pos..].contains(';'))
+}
+
+/// Extracts the keys passed to translation macros in a frontary source file,
+/// looking back up to `context_lines` lines from each string for the macro.
+///
+/// `format!` templates, such as in `ViewString::Key(format!("user-{}", id))`,
+/// build keys at run time and are skipped with a warning unless
+/// `include_format_keys` is set.
+pub(crate) fn extract_frontary_keys_from_file(
+    path: &Path,
+    re: &Regex,
+    context_lines: usize,
+    include_format_keys: bool,
+) -> Result<HashSet<String>, io::Error> {
+    let content = fs::read_to_string(path)?;
+
+    let mut keys: HashSet<_> = re
+        .captures_iter(&content)
+        .filter_map(|cap| cap.get(1))
+        .filter_map(|m| {
+            let matched_string = m.as_str();
+            let start = m.start() - 1;
+            let preceding_lines: Vec<&str> = content[..start]
+                .lines()
+                .rev()
+                .take(context_lines)
+                .map(str::trim)
+                .collect();
+
+            let is_key = preceding_lines.iter().enumerate().any(|(i, line)| {
+                (i == 0 && line.contains("ViewString::Key"))
+                    || (line.contains("text!")
+                        && (i == 0
+                            || (i > 0
+                                && preceding_lines
+                                    .iter()
+                                    .find(|&&l| !l.is_empty())
+                                    .is_some_and(|prev| prev.contains("ctx.props()")))))
+            }) || is_get_text_key(&content, start)
+                || find_enclosing_macro(&content, start, TEXT_MACROS).is_some();
+            if !is_key {
+                return None;
+            }
+            // only templates that would become keys are worth a warning
+            if !include_format_keys && is_format_template(&content, start) {
+                eprintln!(
+                    "⚠️ Skipping format! template {matched_string:?} in {}; pass \
+                     --include-format-keys to keep it",
+                    path.display()
+                );
+                return None;
+            }
+            Some(matched_string.to_string())
+        })
+        .collect();
+    keys.extend(resolve_text_macro_variables(&content));
+
+    Ok(keys)
+}
+
+/// Returns whether the string whose opening quote is at `start` is the key of
+/// a `get_text!(txt, ctx.props().language.tag(), "key")` call, i.e. its third
+/// argument, with the call opened up to `GET_TEXT_SCAN_LINES` lines before.
+fn is_get_text_key(content: &str, start: usize) -> bool {
+    let window_start = content[..start]
+        .rmatch_indices('\n')
+        .nth(GET_TEXT_SCAN_LINES)
+        .map_or(0, |(i, _)| i + 1);
+    content[window_start..start]
+        .rfind("get_text!(")
+        .is_some_and(|call| {
+            let preceding_args = &content[window_start + call + "get_text!(".len()..start];
+            preceding_args.trim_end().ends_with(',')
+                && split_top_level_args(preceding_args).len() == 2
+        })
+}
+
+/// Returns the start of the `macros` invocation enclosing `pos`, if one
+/// begins within the 10 lines before `pos`.
+///
+/// Unlike the fixed look-behind window, this follows the invocation to its
+/// closing `)`, so keys in long multi-line calls are still found.
+fn find_enclosing_macro(content: &str, pos: usize, macros: &[&str]) -> Option<usize> {
+    let window_start = content[..pos]
+        .rmatch_indices('\n')
+        .nth(MACRO_SCAN_LINES)
+        .map_or(0, |(i, _)| i + 1);
+    let window = &content[window_start..pos];
+
+    let mut starts: Vec<(usize, &str)> = macros
+        .iter()
+        .flat_map(|name| {
+            window
+                .match_indices(name)
+                .map(move |(i, _)| (window_start + i, *name))
+        })
+        .collect();
+    // the innermost invocation starts last
+    starts.sort_unstable_by_key(|&(start, _)| Reverse(start));
+    starts.into_iter().find_map(|(start, name)| {
+        let open = start + name.len() - 1;
+        let close = find_closing(content, open, '(', ')')?;
+        (close > pos).then_some(start)
+    })
+}
+
+/// Resolves `text!` calls whose key argument is a variable to the string
+/// literals the variable may hold, following its `let` binding.
+fn resolve_text_macro_variables(content: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    for (call_start, _) in content.match_indices("text!(") {
+        let open = call_start + "text!".len();
+        let Some(close) = find_closing(content, open, '(', ')') else {
+            continue;
+        };
+        let Some(key_arg) = split_top_level_args(&content[open + 1..close])
+            .last()
+            .map(|arg| arg.trim())
+        else {
+            continue;
+        };
+        if IDENTIFIER_RE.is_match(key_arg) {
+            keys.extend(resolve_let_binding(content, key_arg, call_start));
+        }
+    }
+    keys
+}
+
+/// Splits macro or function arguments at commas that are not nested inside
+/// brackets or string literals.
+fn split_top_level_args(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut part_start = 0;
+    for (i, c) in args.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&args[part_start..i]);
+                part_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !args[part_start..].trim().is_empty() {
+        parts.push(&args[part_start..]);
+    }
+    parts
+}
+
+/// Returns the string literals assigned to `name` by its last `let` binding
+/// before `before`, which is either a literal or a `match` expression.
+fn resolve_let_binding(content: &str, name: &str, before: usize) -> Vec<String> {
+    let Ok(binding_re) = Regex::new(&format!(
+        r"\blet\s+(?:mut\s+)?{}\b(?:\s*:[^=;]+)?\s*=\s*",
+        regex::escape(name)
+    )) else {
+        return Vec::new();
+    };
+    let Some(binding) = binding_re.find_iter(&content[..before]).last() else {
+        return Vec::new();
+    };
+
+    let expr = &content[binding.end()..before];
+    if let Some(literal) = LEADING_STRING_RE.captures(expr).and_then(|cap| cap.get(1)) {
+        vec![literal.as_str().to_string()]
+    } else if expr.starts_with("match ") {
+        extract_match_arms(expr)
+    } else {
+        Vec::new()
+    }
+}
+
+/// Extracts the string literals returned by the arms of the `match`
+/// expression at the start of `expr`.
+fn extract_match_arms(expr: &str) -> Vec<String> {
+    let Some(body) = expr
+        .find('{')
+        .and_then(|open| Some(&expr[open..=find_matching_brace(expr, open)?]))
+    else {
+        return Vec::new();
+    };
+    MATCH_ARM_STRING_RE
+        .captures_iter(body)
+        .filter_map(|cap| cap.get(1))
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    /// Runs `collect_strings_from_file` with the default options on `source`.
+    fn extract(source: &str) -> Extraction {
+        let mut file = tempfile::NamedTempFile::new().expect("temporary file");
+        file.write_all(source.as_bytes()).expect("writable file");
+        let re = Regex::new(r#""([^"\\]*(\\.[^"\\]*)*)""#).expect("valid regex");
+        collect_strings_from_file(file.path(), &re, &ExtractOptions::default())
+            .expect("readable file")
+    }
+
+    #[test]
+    fn crlf_line_endings_match_lf() {
+        let source = r#"#[derive(EnumString)]
+enum Kind {
+    #[strum(serialize = "Serialized Name")]
+    Alpha,
+}
+
+#[allow(dead_code)]
+fn view(ctx: &Context) {
+    let config = load().expect("Failed To Load");
+    let title = text!(ctx,
+        "Next Line Key");
+    let label = label.unwrap_or("Fallback Item");
+    let text = "Save Item";
+}
+"#;
+        let lf = extract(source);
+        let crlf = extract(&source.replace('\n', "\r\n"));
+        assert_eq!(crlf.kept, lf.kept);
+        assert_eq!(crlf.filtered, lf.filtered);
+        for key in ["Next Line Key", "Fallback Item", "Save Item"] {
+            assert!(crlf.kept.contains(key), "{key}");
+        }
+        for string in ["Serialized Name", "Failed To Load"] {
+            assert_eq!(
+                crlf.filtered.get(string),
+                Some(&FilterReason::LineContext),
+                "{string}"
+            );
+        }
+    }
+
+    #[test]
+    fn display_impl_strings_are_filtered() {
+        for header in [
+            "impl fmt::Display for Kind",
+            "impl std::fmt::Display for Kind",
+            "impl Display for Kind",
+            "impl<T: Debug> Display for Wrapper<T>",
+        ] {
+            let extraction = extract(&format!(
+                r#"{header} {{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {{
+        let label = match self {{
+            Self::Alpha => "Alpha Label",
+        }};
+        f.write_str(label)
+    }}
+}}
+
+fn view() -> &'static str {{
+    "Save Item"
+}}
+"#
+            ));
+            assert_eq!(
+                extraction.filtered.get("Alpha Label"),
+                Some(&FilterReason::DisplayImpl),
+                "{header}"
+            );
+            assert!(extraction.kept.contains("Save Item"), "{header}");
+        }
+    }
+
+    #[test]
+    fn display_impl_ends_after_brace_char_literals() {
+        let extraction = extract(
+            r#"impl fmt::Display for Brace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", '{')?;
+        let quote = '\'';
+        let label = match self {
+            Self::Open => "Open Brace",
+        };
+        f.write_str(label)
+    }
+}
+
+fn view() -> &'static str {
+    "Save Item"
+}
+"#,
+        );
+        assert_eq!(
+            extraction.filtered.get("Open Brace"),
+            Some(&FilterReason::DisplayImpl)
+        );
+        assert!(extraction.kept.contains("Save Item"));
+    }
+
+    #[test]
+    fn error_impl_strings_are_filtered() {
+        let extraction = extract(
+            r#"impl std::error::Error for Failure {
+    fn description(&self) -> &str {
+        "Failure Reason"
+    }
+}
+"#,
+        );
+        assert_eq!(
+            extraction.filtered.get("Failure Reason"),
+            Some(&FilterReason::ErrorImpl)
+        );
+    }
+
+    #[test]
+    fn fallback_strings_are_kept() {
+        let extraction = extract(
+            r#"fn view(ctx: &Context) {
+    let title = ctx.props().title_second.unwrap_or("item");
+    let name = name.unwrap_or_else(|| "entry");
+    let label = label.map_or("fallback", |label| label.as_str());
+    let key = key.unwrap_or("snake_case_key");
+    let flag = "serde";
+    let lint = "dead_code";
+    let platform = platform.unwrap_or("windows");
+    let os = "linux";
+}
+"#,
+        );
+        for key in ["item", "entry", "fallback", "snake_case_key", "windows"] {
+            assert!(extraction.kept.contains(key), "{key}");
+        }
+        assert_eq!(
+            extraction.filtered.get("serde"),
+            Some(&FilterReason::FeatureFlagLike)
+        );
+        assert_eq!(
+            extraction.filtered.get("dead_code"),
+            Some(&FilterReason::LintName)
+        );
+        assert_eq!(
+            extraction.filtered.get("linux"),
+            Some(&FilterReason::TargetOs)
+        );
+    }
+}
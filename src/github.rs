@@ -0,0 +1,59 @@
+use std::io;
+
+use serde_json::Value;
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+const TAGS_PER_PAGE: usize = 100;
+
+/// Looks up `tag` in the tag list of `owner/repo` through the GitHub API and
+/// returns the SHA of the commit it points to.
+pub(crate) fn fetch_github_tag_sha(
+    owner: &str,
+    repo: &str,
+    tag: &str,
+    token: Option<&str>,
+) -> Result<String, io::Error> {
+    for page in 1.. {
+        let url = format!(
+            "{GITHUB_API_URL}/repos/{owner}/{repo}/tags?per_page={TAGS_PER_PAGE}&page={page}"
+        );
+        let mut request = ureq::get(&url)
+            .header("Accept", "application/vnd.github+json")
+            .header(
+                "User-Agent",
+                concat!("linguist/", env!("CARGO_PKG_VERSION")),
+            );
+        if let Some(token) = token {
+            request = request.header("Authorization", &format!("Bearer {token}"));
+        }
+        let body = request
+            .call()
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|e| io::Error::other(format!("GitHub API request failed: {e}")))?;
+        let tags: Value = serde_json::from_str(&body)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("JSON error: {e}")))?;
+        let tags = tags.as_array().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Unexpected GitHub API response: array of tags expected",
+            )
+        })?;
+
+        if let Some(sha) = tags
+            .iter()
+            .find(|entry| entry.get("name").and_then(Value::as_str) == Some(tag))
+            .and_then(|entry| entry.pointer("/commit/sha"))
+            .and_then(Value::as_str)
+        {
+            return Ok(sha.to_string());
+        }
+        if tags.len() < TAGS_PER_PAGE {
+            break;
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("Tag `{tag}` not found in {owner}/{repo}"),
+    ))
+}
@@ -0,0 +1,536 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use clap::ValueEnum;
+use encoding_rs::{Encoding, UTF_8, UTF_16LE, WINDOWS_1252};
+use quick_xml::Reader;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use serde_json::{Map, Value};
+
+/// Character encoding of the locale files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum LocaleEncoding {
+    #[default]
+    #[value(name = "utf-8")]
+    Utf8,
+    /// Byte order taken from the byte order mark, little-endian without one.
+    #[value(name = "utf-16")]
+    Utf16,
+    #[value(name = "iso-8859-1")]
+    Iso88591,
+    #[value(name = "windows-1252")]
+    Windows1252,
+}
+
+impl LocaleEncoding {
+    /// Decodes `bytes`, returning `None` if they are not valid in this
+    /// encoding. A UTF-8 byte order mark is kept for the caller to handle.
+    fn decode(self, bytes: &[u8]) -> Option<String> {
+        let (encoding, bytes) = match self {
+            Self::Utf8 => (UTF_8, bytes),
+            Self::Utf16 => match Encoding::for_bom(bytes) {
+                Some((encoding, bom_length)) if encoding != UTF_8 => {
+                    (encoding, &bytes[bom_length..])
+                }
+                _ => (UTF_16LE, bytes),
+            },
+            // the WHATWG encoding behind the `iso-8859-1` label
+            Self::Iso88591 | Self::Windows1252 => (WINDOWS_1252, bytes),
+        };
+        encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .map(Cow::into_owned)
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf16 => "UTF-16",
+            Self::Iso88591 => "ISO-8859-1",
+            Self::Windows1252 => "Windows-1252",
+        }
+    }
+}
+
+/// How locale files are decoded before they are parsed.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ReadOptions {
+    /// Whether a leading UTF-8 byte order mark is removed; otherwise a file
+    /// starting with one is rejected.
+    pub(crate) strip_bom: bool,
+    pub(crate) encoding: LocaleEncoding,
+}
+
+/// Reads the translations in a locale file, keyed by translation key.
+pub(crate) trait LocaleReader {
+    fn read(&self, path: &Path, options: ReadOptions) -> Result<Map<String, Value>, io::Error>;
+}
+
+/// Reads flat JSON objects such as `langs/ko-KR.json`.
+pub(crate) struct JsonLocaleReader;
+
+impl LocaleReader for JsonLocaleReader {
+    fn read(&self, path: &Path, options: ReadOptions) -> Result<Map<String, Value>, io::Error> {
+        let content = read_file(path, options)?;
+
+        let json: Value = serde_json::from_str(&content)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("JSON error: {e}")))?;
+
+        if let Value::Object(map) = json {
+            Ok(map)
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidData,
+                "Failed to extract keys. JSON object expected.",
+            ))
+        }
+    }
+}
+
+/// Reads GNU gettext `.po` and `.pot` files, using each `msgid` as a key and
+/// its `msgstr` (or `msgstr[0]`) as the value.
+pub(crate) struct GettextLocaleReader;
+
+impl LocaleReader for GettextLocaleReader {
+    fn read(&self, path: &Path, options: ReadOptions) -> Result<Map<String, Value>, io::Error> {
+        let content = read_file(path, options)?;
+        parse_po(&content).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Gettext error in {}: {e}", path.display()),
+            )
+        })
+    }
+}
+
+/// Reads XLIFF 1.2 `<trans-unit>` and XLIFF 2.0 `<unit>` elements, using each
+/// `id` as a key and the text of its `<target>` as the value.
+pub(crate) struct XliffLocaleReader;
+
+impl LocaleReader for XliffLocaleReader {
+    fn read(&self, path: &Path, options: ReadOptions) -> Result<Map<String, Value>, io::Error> {
+        let content = read_file(path, options)?;
+        parse_xliff(&content).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("XLIFF error in {}: {e}", path.display()),
+            )
+        })
+    }
+}
+
+fn parse_xliff(content: &str) -> Result<Map<String, Value>, quick_xml::Error> {
+    let mut entries = Map::new();
+    let mut reader = Reader::from_str(content);
+    let mut unit: Option<String> = None;
+    // the text of the `<target>` being read, and the finished value
+    let mut target: Option<String> = None;
+    let mut value: Option<String> = None;
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if is_unit(&e) => unit = unit_id(&e)?,
+            Event::Empty(e) if is_unit(&e) => {
+                if let Some(id) = unit_id(&e)? {
+                    entries.insert(id, Value::String(String::new()));
+                }
+            }
+            Event::End(e) if matches!(e.local_name().as_ref(), b"trans-unit" | b"unit") => {
+                target = None;
+                if let Some(id) = unit.take() {
+                    entries.insert(id, Value::String(value.take().unwrap_or_default()));
+                }
+            }
+            // later targets, e.g. in `<alt-trans>`, are alternatives
+            Event::Start(e)
+                if unit.is_some() && value.is_none() && e.local_name().as_ref() == b"target" =>
+            {
+                target = Some(String::new());
+            }
+            Event::End(e) if e.local_name().as_ref() == b"target" => {
+                if let Some(text) = target.take() {
+                    value = Some(text);
+                }
+            }
+            Event::Text(e) => {
+                if let Some(text) = target.as_mut() {
+                    text.push_str(&e.unescape()?);
+                }
+            }
+            Event::CData(e) => {
+                if let Some(text) = target.as_mut() {
+                    text.push_str(&e.decode()?);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+fn is_unit(element: &BytesStart) -> bool {
+    matches!(element.local_name().as_ref(), b"trans-unit" | b"unit")
+}
+
+fn unit_id(element: &BytesStart) -> Result<Option<String>, quick_xml::Error> {
+    Ok(match element.try_get_attribute("id")? {
+        Some(id) => Some(id.unescape_value()?.into_owned()),
+        None => None,
+    })
+}
+
+fn read_file(path: &Path, options: ReadOptions) -> Result<String, io::Error> {
+    let bytes = fs::read(path)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("File error: {e}")))?;
+    let content = options.encoding.decode(&bytes).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Locale file is not valid {}; check --locale-encoding: {}",
+                options.encoding.name(),
+                path.display()
+            ),
+        )
+    })?;
+    match content.strip_prefix('\u{FEFF}') {
+        Some(stripped) if options.strip_bom => Ok(stripped.to_string()),
+        Some(_) => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Locale file has UTF-8 BOM; please remove it or pass --strip-bom: {}",
+                path.display()
+            ),
+        )),
+        None => Ok(content),
+    }
+}
+
+/// The `.po` field that continuation lines are appended to.
+enum PoField {
+    None,
+    Context,
+    Id,
+    Str,
+    Ignored,
+}
+
+fn parse_po(content: &str) -> Result<Map<String, Value>, String> {
+    let mut entries = Map::new();
+    let mut msgid: Option<String> = None;
+    let mut msgstr = String::new();
+    let mut field = PoField::None;
+    let mut finish = |msgid: &mut Option<String>, msgstr: &mut String| {
+        // The entry with an empty msgid is the header, not a translation.
+        if let Some(id) = msgid.take().filter(|id| !id.is_empty()) {
+            entries.insert(id, Value::String(std::mem::take(msgstr)));
+        }
+        msgstr.clear();
+    };
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (keyword, rest) = if line.starts_with('"') {
+            ("", line)
+        } else {
+            line.split_once(char::is_whitespace)
+                .ok_or_else(|| format!("line {}: missing string", index + 1))?
+        };
+        let text =
+            unquote(rest.trim()).ok_or_else(|| format!("line {}: invalid string", index + 1))?;
+        match keyword {
+            "" => match field {
+                PoField::Id => msgid.get_or_insert_default().push_str(&text),
+                PoField::Str => msgstr.push_str(&text),
+                PoField::Context | PoField::Ignored => {}
+                PoField::None => return Err(format!("line {}: unexpected string", index + 1)),
+            },
+            "msgctxt" => {
+                finish(&mut msgid, &mut msgstr);
+                field = PoField::Context;
+            }
+            "msgid" => {
+                if !matches!(field, PoField::Context) {
+                    finish(&mut msgid, &mut msgstr);
+                }
+                msgid = Some(text);
+                field = PoField::Id;
+            }
+            "msgstr" | "msgstr[0]" => {
+                msgstr = text;
+                field = PoField::Str;
+            }
+            "msgid_plural" => field = PoField::Ignored,
+            keyword if keyword.starts_with("msgstr[") => field = PoField::Ignored,
+            keyword => return Err(format!("line {}: unknown keyword `{keyword}`", index + 1)),
+        }
+    }
+    finish(&mut msgid, &mut msgstr);
+    Ok(entries)
+}
+
+/// Strips the quotes from a `.po` string and resolves its escape sequences.
+fn unquote(s: &str) -> Option<String> {
+    let inner = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            other => out.push(other),
+        }
+    }
+    Some(out)
+}
+
+fn escape_po(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r")
+}
+
+/// Writes `keys` as a gettext `.pot` template with empty translations.
+pub(crate) fn write_pot(path: &Path, keys: &HashSet<String>) -> Result<(), io::Error> {
+    let mut keys: Vec<_> = keys.iter().collect();
+    keys.sort();
+    let mut content = format!(
+        "msgid \"\"\nmsgstr \"\"\n\"POT-Creation-Date: {}\\n\"\n\"MIME-Version: 1.0\\n\"\n\
+         \"Content-Type: text/plain; charset=UTF-8\\n\"\n\"Content-Transfer-Encoding: 8bit\\n\"\n",
+        Utc::now().format("%Y-%m-%d %H:%M%z")
+    );
+    content.extend(
+        keys.iter()
+            .map(|key| format!("\nmsgid \"{}\"\nmsgstr \"\"\n", escape_po(key))),
+    );
+    fs::write(path, content)
+        .map_err(|e| Error::new(e.kind(), format!("Failed to write {}: {e}", path.display())))?;
+    eprintln!("💾 Exported {} keys to {}", keys.len(), path.display());
+    Ok(())
+}
+
+/// Writes `keys` as an XLIFF 1.2 skeleton whose units have English sources and
+/// no targets.
+pub(crate) fn write_xliff(path: &Path, keys: &HashSet<String>) -> Result<(), io::Error> {
+    let mut keys: Vec<_> = keys.iter().collect();
+    keys.sort();
+    let mut content = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">\n  \
+         <file original=\"linguist\" source-language=\"en-US\" datatype=\"plaintext\">\n    \
+         <body>\n",
+    );
+    content.extend(keys.iter().map(|key| {
+        let key = escape(key.as_str());
+        format!(
+            "      <trans-unit id=\"{key}\">\n        <source>{key}</source>\n      </trans-unit>\n"
+        )
+    }));
+    content.push_str("    </body>\n  </file>\n</xliff>\n");
+    fs::write(path, content)
+        .map_err(|e| Error::new(e.kind(), format!("Failed to write {}: {e}", path.display())))?;
+    eprintln!("💾 Exported {} keys to {}", keys.len(), path.display());
+    Ok(())
+}
+
+/// File format of the locale files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum LocaleFormat {
+    /// Choose by file extension: `.po`/`.pot` as gettext, `.xlf`/`.xliff` as
+    /// XLIFF, otherwise JSON
+    #[default]
+    Auto,
+    Json,
+    Gettext,
+    Xliff,
+}
+
+impl LocaleFormat {
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Auto => &["json", "po", "pot", "xlf", "xliff"],
+            Self::Json => &["json"],
+            Self::Gettext => &["po", "pot"],
+            Self::Xliff => &["xlf", "xliff"],
+        }
+    }
+
+    fn reader(self, path: &Path) -> &'static dyn LocaleReader {
+        let format = if self == Self::Auto {
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("po" | "pot") => Self::Gettext,
+                Some("xlf" | "xliff") => Self::Xliff,
+                _ => Self::Json,
+            }
+        } else {
+            self
+        };
+        match format {
+            Self::Auto | Self::Json => &JsonLocaleReader,
+            Self::Gettext => &GettextLocaleReader,
+            Self::Xliff => &XliffLocaleReader,
+        }
+    }
+
+    pub(crate) fn read(
+        self,
+        path: &Path,
+        options: ReadOptions,
+    ) -> Result<Map<String, Value>, io::Error> {
+        self.reader(path).read(path, options)
+    }
+
+    /// Returns the file for the language `code` in `dir`, preferring an
+    /// existing file over the default extension of this format.
+    pub(crate) fn locale_file(self, dir: &Path, code: &str) -> PathBuf {
+        let extensions = self.extensions();
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{code}.{ext}")))
+            .find(|path| path.is_file())
+            .unwrap_or_else(|| dir.join(format!("{code}.{}", extensions[0])))
+    }
+}
+
+/// Directories searched, in order, for locale files when `--locale-dir` is not
+/// given.
+const LOCALE_DIR_CANDIDATES: [&str; 5] = ["langs", "locales", "i18n", "assets/i18n", "src/i18n"];
+
+/// Returns the first candidate directory under `ui_repo` that contains at
+/// least one locale file.
+pub(crate) fn discover_locale_dir(ui_repo: &Path) -> Option<PathBuf> {
+    let extensions = LocaleFormat::Auto.extensions();
+    LOCALE_DIR_CANDIDATES
+        .iter()
+        .map(|dir| ui_repo.join(dir))
+        .find(|dir| {
+            fs::read_dir(dir).is_ok_and(|entries| {
+                entries.flatten().any(|entry| {
+                    entry
+                        .path()
+                        .extension()
+                        .is_some_and(|ext| extensions.iter().any(|e| ext == *e))
+                })
+            })
+        })
+}
+
+/// Expands `pattern`, relative to `root`, to the locale files it matches,
+/// sorted by path.
+pub(crate) fn find_locale_files(root: &Path, pattern: &str) -> Result<Vec<PathBuf>, io::Error> {
+    let full_pattern = root.join(pattern);
+    let entries = glob::glob(&full_pattern.to_string_lossy()).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Invalid --locale-pattern `{pattern}`: {e}"),
+        )
+    })?;
+    let mut paths: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .collect();
+    if paths.is_empty() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("No locale files match `{}`", full_pattern.display()),
+        ));
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Returns the files in `dir` with an extension of `format`, sorted by path.
+pub(crate) fn locale_files_in_dir(
+    dir: &Path,
+    format: LocaleFormat,
+) -> Result<Vec<PathBuf>, io::Error> {
+    let extensions = format.extensions();
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| Error::new(e.kind(), format!("Failed to read {}: {e}", dir.display())))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| extensions.iter().any(|e| ext == *e))
+        })
+        .collect();
+    if paths.is_empty() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("No locale files found in {}", dir.display()),
+        ));
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Resolves a locale argument that is either a file path or, when
+/// `locale_dir` is set, a language code such as `ko-KR`.
+pub(crate) fn resolve_locale_path(
+    locale: &str,
+    locale_dir: Option<&Path>,
+    format: LocaleFormat,
+) -> Result<PathBuf, io::Error> {
+    let path = PathBuf::from(locale);
+    if path.is_file() {
+        return Ok(path);
+    }
+    if let Some(dir) = locale_dir {
+        let path = format.locale_file(dir, locale);
+        if path.is_file() {
+            return Ok(path);
+        }
+    }
+    Err(Error::new(
+        ErrorKind::NotFound,
+        format!("Locale `{locale}` is neither a file nor a language code in --locale-dir"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xliff_value_ends_at_target() {
+        let entries = parse_xliff(
+            r#"<xliff version="1.2">
+  <file>
+    <body>
+      <trans-unit id="save">
+        <source>Save</source>
+        <target>Save</target>
+        <note>Button label</note>
+        <alt-trans>
+          <target>Store</target>
+        </alt-trans>
+      </trans-unit>
+      <trans-unit id="cancel">
+        <source>Cancel</source>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>
+"#,
+        )
+        .expect("valid XLIFF");
+        assert_eq!(entries.get("save"), Some(&Value::from("Save")));
+        assert_eq!(entries.get("cancel"), Some(&Value::from("")));
+    }
+}
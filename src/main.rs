@@ -1,31 +1,350 @@
+mod baseline;
+mod check;
+mod config;
+mod csv;
+mod extract;
+mod github;
+mod locale;
 mod repo;
-use std::collections::HashSet;
+mod report;
+mod scan;
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::fs;
-use std::io::{self, Error, ErrorKind};
+use std::io::{self, ErrorKind};
 use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
 
-use clap::Parser;
+use baseline::{diff_missing_keys, print_baseline_diff, read_baseline, save_keys};
+use check::{
+    detect_array_placeholder_mismatches, detect_empty_values, detect_identical_values,
+    detect_placeholder_mismatches, parse_key_format, print_array_placeholder_mismatches,
+    print_case_duplicates, print_empty_values, print_identical_values, print_identifier_keys,
+    print_key_format_violations, print_malformed_keys, print_placeholder_mismatches,
+    print_whitespace_duplicates, warn_substring_exclusions,
+};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
+use config::Config;
+use csv::{import_csv, write_csv};
+use extract::{
+    ExtractOptions, Extraction, FilterReason, collect_strings_from_file,
+    extract_css_classes_and_ids, extract_frontary_keys_from_file, extract_keys_from_data_i18n,
+    extract_keys_from_typescript, html_attribute_regex,
+};
+use github::fetch_github_tag_sha;
+use locale::{
+    LocaleEncoding, LocaleFormat, ReadOptions, discover_locale_dir, find_locale_files,
+    locale_files_in_dir, resolve_locale_path, write_pot, write_xliff,
+};
 use regex::Regex;
-use repo::{RepoManager, validate_ssh_key_path};
-use serde_json::Value;
+use repo::{RepoManager, print_clone_progress, validate_ssh_key_path};
+use report::{
+    ComparisonResult, ExtractionStats, LocaleReport, OutputFormat, compare_keys, key_sources_json,
+    print_locale_reports, print_results, write_locale_stats, write_missing_keys, write_report,
+};
+use scan::{FileScanner, source_dirs};
+use serde_json::{Map, Value};
 use toml::Value as TomlValue;
 
 #[derive(Parser)]
 #[command(author, version, about)]
+#[allow(clippy::struct_excessive_bools)] // each bool is an independent CLI flag
 struct Args {
-    #[arg(long)]
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(long, env = "LINGUIST_UI_PATH")]
     ui_path: Option<PathBuf>,
 
-    #[arg(long)]
+    #[arg(long, env = "LINGUIST_FRONTARY_PATH")]
     frontary_path: Option<PathBuf>,
 
-    #[arg(long, value_name = "SSH_KEY")]
+    /// Clone the UI repo from this `git bundle` file instead of the network
+    #[arg(long, value_name = "PATH", conflicts_with = "ui_path")]
+    ui_bundle: Option<PathBuf>,
+
+    /// Clone the frontary repo from this `git bundle` file instead of the network
+    #[arg(long, value_name = "PATH", conflicts_with = "frontary_path")]
+    frontary_bundle: Option<PathBuf>,
+
+    #[arg(long, value_name = "SSH_KEY", env = "LINGUIST_SSH_KEY")]
     ssh_key: Option<PathBuf>,
+
+    /// Check out this frontary branch instead of the version required by the UI
+    #[arg(long, value_name = "BRANCH", env = "LINGUIST_FRONTARY_BRANCH")]
+    frontary_branch: Option<String>,
+
+    /// Check out this frontary tag instead of the version in the UI's Cargo.toml
+    #[arg(long, value_name = "TAG", conflicts_with = "frontary_branch")]
+    frontary_tag: Option<String>,
+
+    /// GitHub token used to resolve the frontary tag through the GitHub API
+    #[arg(
+        long,
+        value_name = "TOKEN",
+        env = "GITHUB_TOKEN",
+        hide_env_values = true
+    )]
+    github_token: Option<String>,
+
+    /// Path to a TOML configuration file
+    #[arg(long, global = true, value_name = "PATH", env = "LINGUIST_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Directory to skip during source traversal; `*` globs match the name (repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    exclude_dir: Vec<String>,
+
+    /// `.gitignore`-format file of source paths to skip (repeatable)
+    #[arg(long, value_name = "PATH")]
+    ignore_file: Vec<PathBuf>,
+
+    /// Skip source files larger than this many bytes
+    #[arg(long, value_name = "BYTES")]
+    max_file_size: Option<u64>,
+
+    /// Descend at most this many directory levels below each source directory
+    #[arg(long, value_name = "N")]
+    depth_limit: Option<usize>,
+
+    /// Only extract strings from source files modified at or after this ISO
+    /// 8601 date or date-time
+    #[arg(long, value_name = "DATE", value_parser = parse_since)]
+    since: Option<SystemTime>,
+
+    /// Print the strings that were filtered out and why
+    #[arg(long)]
+    list_excluded: bool,
+
+    /// Suggest similar locale keys for missing keys (possible typos)
+    #[arg(long)]
+    suggest_similar: bool,
+
+    /// Warn about extracted keys not fully matching this regex, or the
+    /// `title-case` or `lower-kebab` convention
+    #[arg(long, value_name = "FORMAT", value_parser = parse_key_format)]
+    key_format: Option<Regex>,
+
+    /// Warn about extracted keys that look like `snake_case`, `camelCase`, or
+    /// `PascalCase` code identifiers
+    #[arg(long)]
+    warn_identifiers: bool,
+
+    /// JSON key list from a previous run to diff the extracted keys against
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+
+    /// Report only the missing keys that are not in the `--baseline` key list
+    #[arg(long, requires = "baseline")]
+    report_new_keys_only: bool,
+
+    /// Fail when a locale file is older than DAYS days although the extracted
+    /// keys changed since the `--baseline` run
+    #[arg(long, value_name = "DAYS", requires = "baseline")]
+    fail_if_stale: Option<u64>,
+
+    /// Write the extracted keys to a JSON file for later `--baseline` runs
+    #[arg(long, value_name = "FILE")]
+    save_keys: Option<PathBuf>,
+
+    /// Exclude extracted strings matching this regex (repeatable)
+    #[arg(long, global = true, value_name = "PATTERN")]
+    exclude_regex: Vec<String>,
+
+    /// Always keep extracted strings matching this regex (repeatable)
+    #[arg(long, global = true, value_name = "PATTERN")]
+    include_regex: Vec<String>,
+
+    /// Exclude extracted strings starting with this prefix, in addition to the
+    /// configured `excluded_prefixes` (repeatable)
+    #[arg(long, value_name = "PREFIX")]
+    exclude_prefix: Vec<String>,
+
+    /// Exclude extracted strings longer than N characters
+    #[arg(long, value_name = "N")]
+    max_key_length: Option<usize>,
+
+    /// Exclude extracted strings with fewer than N words
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    min_word_count: usize,
+
+    /// Exclude extracted strings in which fewer than this share of the
+    /// non-whitespace characters are letters
+    #[arg(long, value_name = "RATIO", default_value_t = 0.5, value_parser = parse_ratio)]
+    min_alphabetic_ratio: f64,
+
+    /// Print extraction statistics without reading locale files, like `stats`
+    #[arg(long)]
+    stats_only: bool,
+
+    /// Report completeness separately for each locale file
+    #[arg(long)]
+    per_locale: bool,
+
+    /// Output format of the comparison report
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Warn about locale keys whose value is an empty string
+    #[arg(long, conflicts_with = "error_empty_values")]
+    warn_empty_values: bool,
+
+    /// Fail when a locale key has an empty string value
+    #[arg(long)]
+    error_empty_values: bool,
+
+    /// Fail when the entries of an array value, e.g. plural forms, have
+    /// different placeholders
+    #[arg(long)]
+    validate_array_values: bool,
+
+    /// Key not to report when its value is identical in two locales; repeatable
+    #[arg(long, global = true, value_name = "KEY")]
+    ignore_identical_values: Vec<String>,
+
+    /// Retry over HTTPS when cloning an SSH URL fails, e.g. without an SSH key
+    #[arg(long)]
+    auto_https_fallback: bool,
+
+    /// Keep element text in Leptos `view!` macros, e.g. `<p>"Key"</p>`, as keys
+    #[arg(long)]
+    leptos_mode: bool,
+
+    /// Keep frontary keys that are `format!` templates, which are skipped by default
+    #[arg(long)]
+    include_format_keys: bool,
+
+    /// Keep strings containing Korean or Chinese characters, e.g. bilingual keys
+    #[arg(long)]
+    allow_cjk: bool,
+
+    /// Also collect keys passed to `t`, `i18n`, or `translate` in UI TypeScript files
+    #[arg(long)]
+    scan_typescript: bool,
+
+    /// Check out only sources, manifests, static assets, and locale files
+    #[arg(long)]
+    sparse: bool,
+
+    /// Do not warn when a local repo has uncommitted changes
+    #[arg(long)]
+    ignore_dirty: bool,
+
+    /// Skip checking that the SSH key file exists before cloning
+    #[arg(long)]
+    no_verify: bool,
+
+    /// Do not print clone progress
+    #[arg(long, short)]
+    quiet: bool,
+
+    /// Exit with an error when extracted keys are missing from a locale file
+    #[arg(long)]
+    fail_on_missing: bool,
+
+    /// Exit with an error when more than N extracted keys are missing from
+    /// every locale file
+    #[arg(long, value_name = "N")]
+    max_missing: Option<usize>,
+
+    /// Use CI defaults: --quiet, --fail-on-missing, and --format json unless
+    /// given; implied when CI is detected
+    #[arg(long)]
+    ci: bool,
+
+    /// Skip symbolic links during source traversal
+    #[arg(long)]
+    no_follow_symlinks: bool,
+
+    /// Format of the locale files
+    #[arg(long, global = true, value_enum, default_value_t = LocaleFormat::Auto)]
+    locale_format: LocaleFormat,
+
+    /// Export the extracted keys as a gettext `.pot` template
+    #[arg(long, value_name = "FILE")]
+    export_pot: Option<PathBuf>,
+
+    /// Export the extracted keys as an XLIFF 1.2 skeleton
+    #[arg(long, value_name = "FILE")]
+    export_xliff: Option<PathBuf>,
+
+    /// Export the extracted keys and their locale values as CSV
+    #[arg(long, value_name = "FILE")]
+    export_csv: Option<PathBuf>,
+
+    /// Directory where `missing_keys.json` is written
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    artifacts_dir: PathBuf,
+
+    /// Write the key count and completeness of each locale to a JSON file
+    #[arg(long, value_name = "PATH")]
+    output_locale_stats: Option<PathBuf>,
+
+    /// Also write the comparison results and statistics to a JSON file,
+    /// whatever the output format
+    #[arg(long, value_name = "PATH")]
+    write_report: Option<PathBuf>,
+
+    /// Directory containing the locale files; auto-detected in the UI repo if omitted
+    #[arg(long, global = true, value_name = "DIR")]
+    locale_dir: Option<PathBuf>,
+
+    /// Remove a UTF-8 byte order mark from locale files instead of rejecting them
+    #[arg(long, global = true)]
+    strip_bom: bool,
+
+    /// Character encoding of the locale files
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "ENCODING",
+        default_value_t = LocaleEncoding::Utf8
+    )]
+    locale_encoding: LocaleEncoding,
+
+    /// Glob, relative to the UI repo, matching the locale files to compare
+    #[arg(long, value_name = "GLOB", conflicts_with = "locale_dir")]
+    locale_pattern: Option<String>,
 }
 
+impl Args {
+    fn read_options(&self) -> ReadOptions {
+        ReadOptions {
+            strip_bom: self.strip_bom,
+            encoding: self.locale_encoding,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Compare two locale files directly, without extracting keys from source
+    DiffLocale {
+        /// Path or language code (with --locale-dir) of the first locale
+        lang1: String,
+        /// Path or language code (with --locale-dir) of the second locale
+        lang2: String,
+    },
+    /// Run extraction and print statistics instead of comparing locales
+    Stats,
+    /// Merge values from a CSV written by --export-csv into the JSON locale files
+    ImportCsv {
+        /// Path of the CSV file
+        path: PathBuf,
+        /// Replace values of keys that already exist in the locale files
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Validate the configuration file and the options it is used with
+    CheckConfig,
+}
+
+// The fixed arrays must stay sorted and free of duplicates; `build.rs` checks.
 const FIXED_EXCLUDED_STRINGS: &[&str] = &[
-    "&nbsp;",
-    "\\t",
     "Content-Type",
     "DCE/RPC Blocklist",
     "DNS Blocklist",
@@ -44,11 +363,12 @@ const FIXED_EXCLUDED_STRINGS: &[&str] = &[
     "NTLM Blocklist",
     "Port Scan",
     "RDP Blocklist",
-    "SMTP Blocklist",
     "SMB Blocklist",
+    "SMTP Blocklist",
     "SSH Blocklist",
     "TLS Blocklist",
     "Y-m-d H:i",
+    "\\t",
     "account",
     "allowlist",
     "application/json",
@@ -128,8 +448,8 @@ const FIXED_UI_KEY: &[&str] = &[
     "Save HTTP Files",
     "Save Packets",
     "Save SMTP Files",
-    "Session",
     "Semi-supervised Learning",
+    "Session",
     "System Administrator",
     "Token",
     "URL",
@@ -139,56 +459,275 @@ const FIXED_UI_KEY: &[&str] = &[
 
 const AICE_WEB_URL: &str = "git@github.com:aicers/aice-web.git";
 const FRONTARY_URL: &str = "https://github.com/aicers/frontary.git";
+const FRONTARY_OWNER: &str = "aicers";
 const UI_REPO_NAME: &str = "aice-web";
 const FRONTARY_REPO_NAME: &str = "frontary";
 
+/// Locale whose values the other locales are checked against, if present.
+const REFERENCE_LOCALE: &str = "en-US";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Environment variables set by the CI services whose runs get `--ci` defaults.
+const CI_ENV_VARS: &[&str] = &["CI", "GITHUB_ACTIONS", "GITLAB_CI", "CIRCLECI"];
+
 fn main() -> Result<(), io::Error> {
-    let args = Args::parse();
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    // without arguments there is no key to clone with; explain usage instead
+    if env::args_os().len() == 1
+        && args.config.is_none()
+        && args.ssh_key.is_none()
+        && args.ui_path.is_none()
+        && args.frontary_path.is_none()
+    {
+        Args::command().print_help()?;
+        return Ok(());
+    }
+    if !args.ci && ci_detected() {
+        eprintln!("🤖 CI environment detected; using CI defaults");
+        args.ci = true;
+    }
+    // output is always sorted and never colored, so neither needs a default;
+    // there is no CI-specific format such as GitHub Actions annotations yet
+    if args.ci {
+        args.quiet = true;
+        args.fail_on_missing = true;
+        if !format_given(&matches) {
+            args.format = OutputFormat::Json;
+        }
+    }
+
+    if let Some(Command::DiffLocale {
+        ref lang1,
+        ref lang2,
+    }) = args.command
+    {
+        return diff_locale(lang1, lang2, &args);
+    }
+    if let Some(Command::ImportCsv {
+        ref path,
+        overwrite,
+    }) = args.command
+    {
+        let ui_root = args.ui_path.clone().unwrap_or_default();
+        let locale_dir = args
+            .locale_dir
+            .clone()
+            .or_else(|| discover_locale_dir(&ui_root))
+            .ok_or_else(|| {
+                io::Error::new(
+                    ErrorKind::NotFound,
+                    "No locale directory found; set --locale-dir",
+                )
+            })?;
+        return import_csv(path, &locale_dir, overwrite, args.read_options());
+    }
+    if let Some(Command::CheckConfig) = args.command {
+        return check_config(&args);
+    }
 
-    // Validate SSH key if provided
-    if let Some(ref ssh_key_path) = args.ssh_key {
+    let mut config = match args.config {
+        Some(ref path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    config
+        .excluded_dirs
+        .extend(args.exclude_dir.iter().cloned());
+    config.ignore_files.extend(args.ignore_file.iter().cloned());
+    config
+        .excluded_prefixes
+        .extend(args.exclude_prefix.iter().cloned());
+    if args.no_follow_symlinks {
+        config.follow_symlinks = false;
+    }
+    let options = ExtractOptions {
+        exclude_patterns: compile_patterns("--exclude-regex", &args.exclude_regex)?,
+        include_patterns: compile_patterns("--include-regex", &args.include_regex)?,
+        excluded_prefixes: config.excluded_prefixes.clone(),
+        translation_hooks: config.translation_hook_names.clone(),
+        max_key_length: args.max_key_length,
+        min_word_count: args.min_word_count,
+        min_alphabetic_ratio: args.min_alphabetic_ratio,
+        strum_message_as_key: config.strum_message_as_key,
+        strum_serialize_as_key: config.strum_serialize_as_key,
+        serde_rename_capitalized_as_key: config.serde_rename_capitalized_as_key,
+        context_lines: config.rust_extractor.context_lines,
+        leptos_mode: args.leptos_mode,
+        allow_cjk: args.allow_cjk,
+    };
+
+    // Validate SSH key if provided, unless it will only exist once cloning starts
+    if let Some(ref ssh_key_path) = args.ssh_key
+        && !args.no_verify
+    {
         validate_ssh_key_path(ssh_key_path)
             .map_err(|e| io::Error::other(e.message().to_owned()))?;
     }
 
-    let repo_manager = RepoManager::new(args.ssh_key)
-        .map_err(|e| io::Error::other(format!("Failed to create RepoManager: {e}")))?;
+    let mut repo_manager = RepoManager::new(args.ssh_key.clone())
+        .map_err(|e| io::Error::other(format!("Failed to create RepoManager: {e}")))?
+        .with_https_fallback(args.auto_https_fallback)
+        .with_sparse(args.sparse);
+    if !args.quiet {
+        repo_manager = repo_manager.with_progress_callback(print_clone_progress);
+    }
 
     log_repo_strategy(args.ui_path.as_ref(), args.frontary_path.as_ref());
 
-    let ui_repo = prepare_repo(
-        AICE_WEB_URL,
-        args.ui_path.clone(),
-        UI_REPO_NAME,
-        &repo_manager,
-    )?;
+    let (ui_repo, fr_repo) = prepare_repos(&args, &repo_manager)?;
+    run_comparison(&ui_repo, &fr_repo, &args, &config, &options)
+}
 
-    let fr_repo = prepare_repo(
-        FRONTARY_URL,
-        args.frontary_path.clone(),
-        FRONTARY_REPO_NAME,
-        &repo_manager,
-    )?;
+fn diff_locale(lang1: &str, lang2: &str, args: &Args) -> Result<(), io::Error> {
+    let locale_dir = args.locale_dir.as_deref();
+    let path1 = resolve_locale_path(lang1, locale_dir, args.locale_format)?;
+    let path2 = resolve_locale_path(lang2, locale_dir, args.locale_format)?;
+    let map1 = args.locale_format.read(&path1, args.read_options())?;
+    let map2 = args.locale_format.read(&path2, args.read_options())?;
+    let keys1: HashSet<String> = map1.keys().cloned().collect();
+    let keys2: HashSet<String> = map2.keys().cloned().collect();
+    let mut identical = detect_identical_values(&map1, &map2);
+    identical.retain(|key| !args.ignore_identical_values.contains(key));
+
+    let name1 = file_name(&path1);
+    let name2 = file_name(&path2);
+    let comparison = compare_keys(&name1, &keys1, &name2, &keys2, false);
+    if args.format == OutputFormat::Json {
+        let mut json = comparison.to_json();
+        json["identical_values"] = identical.into();
+        println!("{json:#}");
+    } else {
+        print_results(&[comparison], args.format, &Value::Null, &mut io::stdout())?;
+        print_identical_values(&name1, &name2, &identical);
+    }
+    Ok(())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+/// Validates the configuration file, regex options, and locale directory
+/// without running extraction, printing one line per check.
+fn check_config(args: &Args) -> Result<(), io::Error> {
+    let mut failures = 0;
+    let mut report = |check: &str, result: Result<(), String>| match result {
+        Ok(()) => println!("✅ {check}"),
+        Err(detail) => {
+            failures += 1;
+            println!("✗ {check}: {detail}");
+        }
+    };
+
+    match args.config {
+        Some(ref path) => match config::read_toml(path) {
+            Ok(value) => {
+                report(&format!("Parse {}", path.display()), Ok(()));
+                let unknown = config::unknown_keys(&value);
+                report(
+                    "No unknown keys",
+                    if unknown.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(format!("unknown key(s) `{}`", unknown.join("`, `")))
+                    },
+                );
+                report(
+                    "Value types",
+                    Config::from_value(&value)
+                        .map(drop)
+                        .map_err(|e| e.to_string()),
+                );
+            }
+            Err(e) => report(&format!("Parse {}", path.display()), Err(e.to_string())),
+        },
+        None => report(
+            "Configuration file",
+            Err("none given; pass --config <PATH> or set LINGUIST_CONFIG".to_string()),
+        ),
+    }
+
+    for (flag, patterns) in [
+        ("--exclude-regex", &args.exclude_regex),
+        ("--include-regex", &args.include_regex),
+    ] {
+        if !patterns.is_empty() {
+            report(
+                &format!("Compile {flag} patterns"),
+                compile_patterns(flag, patterns)
+                    .map(drop)
+                    .map_err(|e| e.to_string()),
+            );
+        }
+    }
 
-    checkout_frontary(args.frontary_path.as_ref(), &ui_repo, &fr_repo)?;
-    process_keys(&ui_repo, &fr_repo)?;
+    if let Some(ref dir) = args.locale_dir {
+        if dir.is_dir() {
+            report(&format!("Locale directory {}", dir.display()), Ok(()));
+            match locale_files_in_dir(dir, args.locale_format) {
+                Ok(paths) => {
+                    for path in paths {
+                        report(
+                            &format!("Parse {}", path.display()),
+                            args.locale_format
+                                .read(&path, args.read_options())
+                                .map(drop)
+                                .map_err(|e| e.to_string()),
+                        );
+                    }
+                }
+                Err(e) => report("Locale files", Err(e.to_string())),
+            }
+        } else {
+            report(
+                &format!("Locale directory {}", dir.display()),
+                Err("not a directory".to_string()),
+            );
+        }
+    }
+
+    if failures > 0 {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("{failures} configuration check(s) failed"),
+        ));
+    }
     Ok(())
 }
 
+fn compile_patterns(flag: &str, patterns: &[String]) -> Result<Vec<Regex>, io::Error> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern).map_err(|e| {
+                io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Invalid {flag} pattern `{pattern}`: {e}"),
+                )
+            })
+        })
+        .collect()
+}
+
 fn log_repo_strategy(ui_path: Option<&PathBuf>, fr_path: Option<&PathBuf>) {
     match (ui_path, fr_path) {
-        (None, None) => println!(
+        (None, None) => eprintln!(
             "🔄 No local paths: will clone both '{UI_REPO_NAME}' and '{FRONTARY_REPO_NAME}'."
         ),
-        (Some(path), None) => println!(
+        (Some(path), None) => eprintln!(
             "🔄 Using local {UI_REPO_NAME} at {}; will clone {FRONTARY_REPO_NAME}.",
             path.display()
         ),
-        (None, Some(path)) => println!(
+        (None, Some(path)) => eprintln!(
             "🔄 Will clone {UI_REPO_NAME}; using local {FRONTARY_REPO_NAME} at {}.",
             path.display()
         ),
-        (Some(ui), Some(fr)) => println!(
+        (Some(ui), Some(fr)) => eprintln!(
             "🔄 Using local {UI_REPO_NAME} at {} and {FRONTARY_REPO_NAME} at {}.",
             ui.display(),
             fr.display()
@@ -196,28 +735,198 @@ fn log_repo_strategy(ui_path: Option<&PathBuf>, fr_path: Option<&PathBuf>) {
     }
 }
 
-fn checkout_frontary(
-    fr_local: Option<&PathBuf>,
+/// Prepares the UI and frontary repositories. When both are cloned, frontary
+/// is cloned on another thread while the UI is cloned, and checked out at the
+/// tag the UI requires once both are done.
+fn prepare_repos(args: &Args, manager: &RepoManager) -> Result<(PathBuf, PathBuf), io::Error> {
+    // with a token only the tagged commit is fetched, which needs the tag first
+    if args.ui_path.is_some() || args.frontary_path.is_some() || args.github_token.is_some() {
+        let ui_repo = prepare_ui(args, manager)?;
+        let fr_repo = prepare_frontary(args, &ui_repo, manager)?;
+        return Ok((ui_repo, fr_repo));
+    }
+
+    let frontary = {
+        // progress of both clones would overwrite each other on one line
+        let manager = manager.clone().without_progress_callback();
+        let bundle = args.frontary_bundle.clone();
+        let ignore_dirty = args.ignore_dirty;
+        let tag_override = args
+            .frontary_tag
+            .clone()
+            .filter(|_| args.frontary_branch.is_none() && bundle.is_none());
+        thread::spawn(move || {
+            if let Some(ref tag) = tag_override {
+                verify_remote_tag(&manager, tag)?;
+            }
+            prepare_repo(
+                FRONTARY_URL,
+                None,
+                bundle.as_deref(),
+                FRONTARY_REPO_NAME,
+                &manager,
+                ignore_dirty,
+            )
+        })
+    };
+    let ui_repo = prepare_ui(args, manager);
+    // a missing tag fails here without waiting for the frontary clone
+    let tag = match ui_repo {
+        Ok(ref ui_repo) if args.frontary_branch.is_none() => {
+            let tag = frontary_tag(args.frontary_tag.as_deref(), ui_repo)?;
+            if args.frontary_tag.is_none() && args.frontary_bundle.is_none() {
+                verify_remote_tag(manager, &tag)?;
+            }
+            Some(tag)
+        }
+        _ => None,
+    };
+    let fr_repo = frontary
+        .join()
+        .map_err(|_| io::Error::other("Cloning frontary panicked"))?;
+    let (ui_repo, fr_repo) = match (ui_repo, fr_repo) {
+        (Ok(ui_repo), Ok(fr_repo)) => (ui_repo, fr_repo),
+        (Err(ui_err), Err(fr_err)) => return Err(io::Error::other(format!("{ui_err}; {fr_err}"))),
+        (Err(e), Ok(_)) | (Ok(_), Err(e)) => return Err(e),
+    };
+
+    checkout_frontary(args, tag.as_deref(), &fr_repo)?;
+    Ok((ui_repo, fr_repo))
+}
+
+fn prepare_ui(args: &Args, manager: &RepoManager) -> Result<PathBuf, io::Error> {
+    prepare_repo(
+        AICE_WEB_URL,
+        args.ui_path.clone(),
+        args.ui_bundle.as_deref(),
+        UI_REPO_NAME,
+        manager,
+        args.ignore_dirty,
+    )
+}
+
+fn prepare_frontary(
+    args: &Args,
     ui_repo: &Path,
-    fr_repo: &Path,
-) -> Result<(), io::Error> {
-    if fr_local.is_none() {
-        let tag = read_frontary_req(ui_repo)?;
-        println!("🔀 Checking out frontary at commit: {tag}");
-        RepoManager::checkout(fr_repo, &tag)
-            .map_err(|e| io::Error::other(format!("Checkout failed: {e}")))?;
+    manager: &RepoManager,
+) -> Result<PathBuf, io::Error> {
+    let tag = if args.frontary_path.is_none() && args.frontary_branch.is_none() {
+        Some(frontary_tag(args.frontary_tag.as_deref(), ui_repo)?)
+    } else {
+        None
+    };
+    // a bundle is used where the network is not available
+    if let Some(ref tag) = tag
+        && args.frontary_bundle.is_none()
+    {
+        if let Some(ref token) = args.github_token {
+            match fetch_github_tag_sha(FRONTARY_OWNER, FRONTARY_REPO_NAME, tag, Some(token)) {
+                Ok(sha) => {
+                    eprintln!("🔀 Fetching frontary at {tag} ({sha})");
+                    return manager
+                        .clone_at_commit(FRONTARY_URL, FRONTARY_REPO_NAME, &sha)
+                        .map_err(|e| io::Error::other(format!("Failed to fetch frontary: {e}")));
+                }
+                Err(e) => eprintln!("⚠️ Could not resolve frontary tag {tag} via GitHub: {e}"),
+            }
+        }
+        verify_remote_tag(manager, tag)?;
+    }
+
+    let fr_repo = prepare_repo(
+        FRONTARY_URL,
+        args.frontary_path.clone(),
+        args.frontary_bundle.as_deref(),
+        FRONTARY_REPO_NAME,
+        manager,
+        args.ignore_dirty,
+    )?;
+    checkout_frontary(args, tag.as_deref(), &fr_repo)?;
+    Ok(fr_repo)
+}
+
+/// Returns the `--frontary-tag` override, or the version the UI requires.
+fn frontary_tag(tag_override: Option<&str>, ui_repo: &Path) -> Result<String, io::Error> {
+    if let Some(tag) = tag_override {
+        eprintln!("📌 Using frontary tag override: {tag}");
+        return Ok(tag.to_string());
+    }
+    read_frontary_req(ui_repo)
+}
+
+/// Checks that `tag` exists in the frontary remote before starting the clone.
+///
+/// Commit hashes are not checked, and a failure to list the remote tags only
+/// prints a warning, leaving the clone to report the problem.
+fn verify_remote_tag(manager: &RepoManager, tag: &str) -> Result<(), io::Error> {
+    if tag.len() >= 7 && tag.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(());
+    }
+    match RepoManager::ls_remote_tags(FRONTARY_URL, manager.ssh_key_path.as_deref()) {
+        Ok(tags) => {
+            if tags.iter().any(|existing| existing == tag) {
+                Ok(())
+            } else {
+                Err(io::Error::new(
+                    ErrorKind::NotFound,
+                    format!("Tag `{tag}` does not exist in frontary"),
+                ))
+            }
+        }
+        Err(e) => {
+            eprintln!("⚠️ Could not list frontary tags: {}", e.message());
+            Ok(())
+        }
+    }
+}
+
+fn checkout_frontary(args: &Args, tag: Option<&str>, fr_repo: &Path) -> Result<(), io::Error> {
+    if args.frontary_path.is_some() {
+        return Ok(());
     }
+
+    let reference = if let Some(ref branch) = args.frontary_branch {
+        eprintln!("🔀 Checking out frontary branch: {branch}");
+        // a fresh clone only has a local branch for the default branch
+        format!("origin/{branch}")
+    } else if let Some(tag) = tag {
+        if args.frontary_tag.is_some() {
+            let tags = RepoManager::list_tags(fr_repo)
+                .map_err(|e| io::Error::other(format!("Failed to list frontary tags: {e}")))?;
+            if !tags.iter().any(|existing| existing == tag) {
+                return Err(io::Error::new(
+                    ErrorKind::NotFound,
+                    format!("Tag `{tag}` does not exist in frontary"),
+                ));
+            }
+        }
+        eprintln!("🔀 Checking out frontary at commit: {tag}");
+        tag.to_string()
+    } else {
+        return Ok(());
+    };
+    RepoManager::checkout(fr_repo, &reference)
+        .map_err(|e| io::Error::other(format!("Checkout failed: {e}")))?;
     Ok(())
 }
 
 fn prepare_repo(
     repo_url: &str,
     override_path: Option<PathBuf>,
+    bundle: Option<&Path>,
     name: &str,
     manager: &RepoManager,
+    ignore_dirty: bool,
 ) -> Result<PathBuf, io::Error> {
     if let Some(path) = override_path {
         if path.exists() {
+            // a directory that is not a git repository is used as is
+            if !ignore_dirty && RepoManager::is_dirty(&path).unwrap_or(false) {
+                eprintln!(
+                    "⚠️ Local repo has uncommitted changes; results may not match HEAD: {}",
+                    path.display()
+                );
+            }
             return Ok(path);
         }
         return Err(io::Error::new(
@@ -226,7 +935,14 @@ fn prepare_repo(
         ));
     }
 
-    println!("🛠️ Cloning repository: {repo_url}...");
+    if let Some(bundle) = bundle {
+        eprintln!("🛠️ Cloning {name} from bundle: {}...", bundle.display());
+        return manager
+            .clone_from_bundle(bundle, name)
+            .map_err(|e| io::Error::other(format!("Failed to clone {name}: {e}")));
+    }
+
+    eprintln!("🛠️ Cloning repository: {repo_url}...");
 
     let cloned = manager
         .clone_repo(repo_url, name)
@@ -234,314 +950,658 @@ fn prepare_repo(
     Ok(cloned)
 }
 
-fn process_keys(ui_repo: &Path, fr_repo: &Path) -> Result<(), io::Error> {
+/// Keys extracted from the UI and frontary sources, with the counts reported
+/// by the `stats` subcommand.
+struct SourceKeys {
+    ui: HashSet<String>,
+    frontary: HashSet<String>,
+    stats: ExtractionStats,
+}
+
+fn extract_source_keys(
+    ui_repo: &Path,
+    fr_repo: &Path,
+    args: &Args,
+    config: &Config,
+    options: &ExtractOptions,
+) -> Result<SourceKeys, io::Error> {
     // collect paths & files
-    let en_path = ui_repo.join("langs/en-US.json");
-    let ko_path = ui_repo.join("langs/ko-KR.json");
-    let ui_files = get_files_with_extension(ui_repo.join("src"), "rs")?;
-    let css_files = get_files_with_extension(ui_repo.join("static"), "css")?;
-    let frontary_files = get_files_with_extension(fr_repo.join("src"), "rs")?;
+    let ui_scanner = FileScanner::new(ui_repo, config)?
+        .with_max_file_size(args.max_file_size)
+        .with_depth_limit(args.depth_limit);
+    // CSS selectors are excluded however old the stylesheet is
+    let css_files = ui_scanner.files_with_extension(ui_repo.join("static"), "css")?;
+    let ui_scanner = ui_scanner.with_modified_since(args.since);
+    let fr_scanner = FileScanner::new(fr_repo, config)?
+        .with_max_file_size(args.max_file_size)
+        .with_depth_limit(args.depth_limit)
+        .with_modified_since(args.since);
+    let mut ui_files = Vec::new();
+    for dir in source_dirs(ui_repo)? {
+        ui_files.extend(ui_scanner.files_with_extension(dir, "rs")?);
+    }
+    let html_files = ui_scanner.files_with_extension(ui_repo.join("static"), "html")?;
+    let mut typescript_files = Vec::new();
+    if args.scan_typescript {
+        for extension in ["ts", "tsx"] {
+            typescript_files
+                .extend(ui_scanner.files_with_extension(ui_repo.join("src"), extension)?);
+        }
+    }
+    let frontary_files = fr_scanner.files_with_extension(fr_repo.join("src"), "rs")?;
+    let (ui_rust_files, css_count, html_count, typescript_count, frontary_count) = (
+        ui_files.len(),
+        css_files.len(),
+        html_files.len(),
+        typescript_files.len(),
+        frontary_files.len(),
+    );
     let css_ids = extract_css_classes_and_ids(&css_files)?;
-    // JSON keys
-    let en_keys = extract_keys_from_json(&en_path)?;
-    let ko_keys = extract_keys_from_json(&ko_path)?;
     // regex for string literals
     let re = Regex::new(r#""([^"\\]*(\\.[^"\\]*)*)""#)
         .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
 
-    let mut ui_strings = ui_files
-        .into_iter()
-        .map(|p| collect_strings_from_file(&p, &re))
-        .flat_map(Result::into_iter)
-        .flatten()
-        .collect::<HashSet<_>>();
-    ui_strings.retain(|s| {
-        !FIXED_EXCLUDED_STRINGS.iter().any(|&e| e == s) && !css_ids.iter().any(|id| id == s)
-    });
+    let mut extraction = Extraction::default();
+    for path in ui_files {
+        if let Ok(file_extraction) = collect_strings_from_file(&path, &re, options) {
+            extraction.merge(file_extraction);
+        }
+    }
+    let Extraction {
+        kept: mut ui_strings,
+        mut filtered,
+    } = extraction;
+    exclude_fixed_and_css(&mut ui_strings, &mut filtered, &css_ids, options);
+    let filtered_counts = ExtractionStats::count_filtered(&filtered, &ui_strings);
+    let strings_found = ui_strings.len() + filtered_counts.values().sum::<usize>();
+    let organic_ui_keys = ui_strings.len();
     ui_strings.extend(FIXED_UI_KEY.iter().map(ToString::to_string));
+    let fixed_ui_keys = ui_strings.len() - organic_ui_keys;
+    let html_re = html_attribute_regex(&config.html_i18n_attributes)?;
+    for path in html_files {
+        ui_strings.extend(extract_keys_from_data_i18n(&path, &html_re)?);
+    }
+    for path in typescript_files {
+        ui_strings.extend(extract_keys_from_typescript(&path)?);
+    }
+    if args.list_excluded && args.format == OutputFormat::Text {
+        print_filtered(&filtered, &ui_strings);
+    }
 
     let mut frontary_strings = frontary_files
         .into_iter()
-        .map(|p| extract_frontary_keys_from_file(&p, &re))
+        .map(|p| {
+            extract_frontary_keys_from_file(
+                &p,
+                &re,
+                config.frontary_extractor.context_lines,
+                args.include_format_keys,
+            )
+        })
         .flat_map(Result::into_iter)
         .flatten()
         .collect::<HashSet<_>>();
+    let organic_frontary_keys = frontary_strings.len();
     frontary_strings.extend(FIXED_FRONTARY_KEY.iter().map(ToString::to_string));
+    let fixed_frontary_keys = frontary_strings.len() - organic_frontary_keys;
 
-    let all_strings = ui_strings.union(&frontary_strings).cloned().collect();
-    compare_keys("all_strings", &all_strings, "ko-KR.json", &ko_keys);
-    compare_keys("all_strings", &all_strings, "en-US.json", &en_keys);
-    compare_keys("ko-KR.json", &ko_keys, "en-US.json", &en_keys);
-    Ok(())
+    let stats = ExtractionStats {
+        ui_rust_files,
+        css_files: css_count,
+        html_files: html_count,
+        typescript_files: typescript_count,
+        frontary_files: frontary_count,
+        strings_found,
+        filtered: filtered_counts,
+        ui_keys: ui_strings.len(),
+        frontary_keys: frontary_strings.len(),
+        fixed_ui_keys,
+        fixed_frontary_keys,
+        total_keys: ui_strings.union(&frontary_strings).count(),
+    };
+    Ok(SourceKeys {
+        ui: ui_strings,
+        frontary: frontary_strings,
+        stats,
+    })
 }
 
-fn read_frontary_req(ui_root: &Path) -> Result<String, io::Error> {
-    let cargo_toml = ui_root.join("Cargo.toml");
-    let toml_str = fs::read_to_string(&cargo_toml).map_err(|e| {
-        io::Error::new(
-            e.kind(),
-            format!("Failed to read {}: {e}", cargo_toml.display()),
-        )
-    })?;
+/// A locale file and the entries read from it.
+struct Locale {
+    /// File name, e.g. `ko-KR.json`, used in reports.
+    name: String,
+    /// File stem, e.g. `ko-KR`, naming the language.
+    code: String,
+    path: PathBuf,
+    entries: Map<String, Value>,
+    keys: HashSet<String>,
+}
 
-    let cargo: TomlValue = toml::from_str(&toml_str)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid TOML: {e}")))?;
+/// Reads the locale files matching `--locale-pattern`, or all locale files in
+/// the locale directory.
+fn load_locales(ui_repo: &Path, args: &Args) -> Result<Vec<Locale>, io::Error> {
+    let paths = match args.locale_pattern {
+        Some(ref pattern) => find_locale_files(ui_repo, pattern)?,
+        None => locale_files_in_dir(
+            &locale_dir(ui_repo, args.locale_dir.as_deref()),
+            args.locale_format,
+        )?,
+    };
+    paths
+        .into_iter()
+        .map(|path| {
+            let entries = args.locale_format.read(&path, args.read_options())?;
+            Ok(Locale {
+                name: file_name(&path),
+                code: path
+                    .file_stem()
+                    .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned()),
+                keys: entries.keys().cloned().collect(),
+                entries,
+                path,
+            })
+        })
+        .collect()
+}
 
-    if let Some(frontary) = cargo
-        .get("dependencies")
-        .and_then(|deps| deps.get("frontary"))
-    {
-        if let Some(version) = frontary.as_str() {
-            return Ok(version.to_string());
+/// Returns the locale other locales are checked against: `en-US`, or else the
+/// first one.
+fn reference_locale(locales: &[Locale]) -> Option<&Locale> {
+    locales
+        .iter()
+        .find(|locale| locale.code == REFERENCE_LOCALE)
+        .or_else(|| locales.first())
+}
+
+/// Prints the keys whose placeholders differ from the reference locale and,
+/// with `--validate-array-values`, between the entries of an array value,
+/// returning how many were found.
+fn report_placeholder_mismatches(locales: &[Locale], args: &Args) -> usize {
+    let Some(reference) = reference_locale(locales) else {
+        return 0;
+    };
+    let mut count = 0;
+    for locale in locales {
+        if locale.path == reference.path {
+            continue;
         }
-        if let Some(table) = frontary.as_table() {
-            if let Some(tag) = table.get("tag").and_then(TomlValue::as_str) {
-                return Ok(tag.to_string());
-            }
-            if let Some(rev) = table.get("rev").and_then(TomlValue::as_str) {
-                return Ok(rev.to_string());
-            }
-            if let Some(ver) = table.get("version").and_then(TomlValue::as_str) {
-                return Ok(ver.to_string());
+        let mismatches = detect_placeholder_mismatches(&reference.entries, &locale.entries);
+        if args.format == OutputFormat::Text {
+            print_placeholder_mismatches(&reference.name, &locale.name, &mismatches);
+        }
+        count += mismatches.len();
+    }
+    if args.validate_array_values {
+        for locale in locales {
+            let mismatches = detect_array_placeholder_mismatches(&locale.entries);
+            if args.format == OutputFormat::Text {
+                print_array_placeholder_mismatches(&locale.name, &mismatches);
             }
+            count += mismatches.len();
         }
     }
-
-    Err(io::Error::new(
-        io::ErrorKind::NotFound,
-        "`frontary` dependency not found in Cargo.toml",
-    ))
+    count
 }
 
-fn extract_keys_from_json<P: AsRef<Path>>(path: P) -> Result<HashSet<String>, io::Error> {
-    let content = fs::read_to_string(path.as_ref())
-        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("File error: {e}")))?;
+/// Prints keys that are likely mistakes: near duplicates, malformed keys, and,
+/// compared with the `en-US` locale or else the first one, untranslated values.
+fn print_key_warnings(all_strings: &HashSet<String>, locales: &[Locale], args: &Args) {
+    print_case_duplicates("all_strings", all_strings);
+    print_whitespace_duplicates("all_strings", all_strings);
+    print_malformed_keys("all_strings", all_strings);
+    if let Some(ref format) = args.key_format {
+        print_key_format_violations("all_strings", all_strings, format);
+    }
+    if args.warn_identifiers {
+        print_identifier_keys("all_strings", all_strings);
+    }
+    warn_substring_exclusions(FIXED_EXCLUDED_STRINGS, all_strings);
+    for locale in locales {
+        print_case_duplicates(&locale.name, &locale.keys);
+        print_whitespace_duplicates(&locale.name, &locale.keys);
+        print_malformed_keys(&locale.name, &locale.keys);
+    }
+
+    let Some(reference) = reference_locale(locales) else {
+        return;
+    };
+    for locale in locales {
+        if locale.path == reference.path {
+            continue;
+        }
+        let mut identical = detect_identical_values(&reference.entries, &locale.entries);
+        identical.retain(|key| !args.ignore_identical_values.contains(key));
+        print_identical_values(&reference.name, &locale.name, &identical);
+    }
+}
 
-    let json: Value = serde_json::from_str(&content)
-        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("JSON error: {e}")))?;
+/// Extracts the source keys, compares them with the locale files, and prints
+/// the results.
+fn run_comparison(
+    ui_repo: &Path,
+    fr_repo: &Path,
+    args: &Args,
+    config: &Config,
+    options: &ExtractOptions,
+) -> Result<(), io::Error> {
+    let source_keys = extract_source_keys(ui_repo, fr_repo, args, config, options)?;
+    // statistics need no locale files, which may not exist yet
+    if args.stats_only || matches!(args.command, Some(Command::Stats)) {
+        source_keys.stats.print(args.format);
+        return Ok(());
+    }
+    let locales = load_locales(ui_repo, args)?;
+    let all_strings: HashSet<String> = source_keys
+        .ui
+        .union(&source_keys.frontary)
+        .cloned()
+        .collect();
+    let baseline = args.baseline.as_deref().map(read_baseline).transpose()?;
+    report_key_checks(ui_repo, &all_strings, &locales, baseline.as_ref(), args)?;
+    let results = process_keys(&all_strings, &locales, baseline.as_ref(), args)?;
+    let json: Vec<_> = results.iter().map(ComparisonResult::to_json).collect();
+    write_missing_keys(&args.artifacts_dir, &json)?;
+    let empty_values = report_empty_values(&locales, args)?;
+    let placeholder_mismatches = report_placeholder_mismatches(&locales, args);
+    let stale_locales = match (args.fail_if_stale, baseline) {
+        (Some(days), Some(baseline)) if baseline != all_strings => {
+            report_stale_locales(&locales, days)?
+        }
+        _ => 0,
+    };
 
-    if let Value::Object(map) = json {
-        Ok(map.keys().cloned().collect())
+    let key_sources = key_sources_json(&source_keys.ui, &source_keys.frontary);
+    let locale_stats: Vec<_> = locales
+        .iter()
+        .map(|locale| LocaleReport::new(&locale.code, &all_strings, &locale.keys))
+        .collect();
+    if let Some(ref path) = args.output_locale_stats {
+        write_locale_stats(path, &locale_stats)?;
+    }
+    if let Some(ref path) = args.write_report {
+        write_report(
+            path,
+            &results,
+            &key_sources,
+            &source_keys.stats,
+            &locale_stats,
+        )?;
+    }
+    if args.per_locale {
+        let reports: Vec<_> = locales
+            .iter()
+            .map(|locale| LocaleReport::new(&locale.name, &all_strings, &locale.keys))
+            .collect();
+        print_locale_reports(&reports, args.format);
     } else {
-        Err(Error::new(
-            ErrorKind::InvalidData,
-            "Failed to extract keys. JSON object expected.",
-        ))
+        print_results(&results, args.format, &key_sources, &mut io::stdout())?;
+    }
+    let missing_keys = keys_missing_from_every_locale(&results);
+    if let Some(max) = args.max_missing {
+        eprintln!(
+            "Keys missing from every locale file: {} (--max-missing {max})",
+            missing_keys.len()
+        );
     }
-}
 
-fn get_files_with_extension<P: AsRef<Path>>(
-    dir: P,
-    extension: &str,
-) -> Result<Vec<PathBuf>, io::Error> {
-    let mut files = Vec::new();
-    collect_files_with_extension(dir.as_ref(), &mut files, extension)?;
-    Ok(files)
+    if empty_values > 0 {
+        return Err(io::Error::other(format!(
+            "{empty_values} empty translation(s) found"
+        )));
+    }
+    if placeholder_mismatches > 0 {
+        return Err(io::Error::other(format!(
+            "{placeholder_mismatches} placeholder mismatch(es) found"
+        )));
+    }
+    if stale_locales > 0 {
+        return Err(io::Error::other(format!(
+            "{stale_locales} stale locale file(s) found"
+        )));
+    }
+    if let Some(max) = args.max_missing
+        && missing_keys.len() > max
+    {
+        return Err(io::Error::other(format!(
+            "{} missing key(s) exceed --max-missing {max}",
+            missing_keys.len()
+        )));
+    }
+    if args.fail_on_missing {
+        let missing: usize = results
+            .iter()
+            .filter(|result| result.left_name == "all_strings")
+            .map(|result| result.missing_in_right.len())
+            .sum();
+        if missing > 0 {
+            return Err(io::Error::other(format!(
+                "{missing} missing translation(s) found"
+            )));
+        }
+    }
+    Ok(())
 }
 
-fn collect_files_with_extension(
-    dir: &Path,
-    files: &mut Vec<PathBuf>,
-    extension: &str,
+/// Prints likely mistakes in the extracted keys and locale files and the
+/// changes since the baseline, and writes the requested exports.
+fn report_key_checks(
+    ui_repo: &Path,
+    all_strings: &HashSet<String>,
+    locales: &[Locale],
+    baseline: Option<&HashSet<String>>,
+    args: &Args,
 ) -> Result<(), io::Error> {
-    //Define paths to exclude
-    let exclude_paths: HashSet<PathBuf> = vec![
-        PathBuf::from("src/triage/policy/data.rs"),
-        PathBuf::from("src/detection/mitre.rs"),
-    ]
-    .into_iter()
-    .collect();
-
-    fs::read_dir(dir)?
-        .filter_map(Result::ok)
-        .map(|entry| entry.path())
-        .try_for_each(|path| {
-            if path.is_dir() {
-                if !path.ends_with("src/bin") {
-                    collect_files_with_extension(&path, files, extension)?;
-                }
-            } else if path.extension().and_then(|ext| ext.to_str()) == Some(extension)
-                && !exclude_paths.iter().any(|p| path.ends_with(p))
-            {
-                files.push(path);
-            }
-            Ok(())
+    if args.format == OutputFormat::Text {
+        print_key_warnings(all_strings, locales, args);
+    }
+    let csv_locales: Vec<_> = locales
+        .iter()
+        .map(|locale| (locale.code.as_str(), &locale.entries))
+        .collect();
+    write_exports(ui_repo, all_strings, &csv_locales, args)?;
+    if let Some(baseline) = baseline
+        && args.format == OutputFormat::Text
+    {
+        print_baseline_diff(baseline, all_strings);
+    }
+    Ok(())
+}
+
+/// Returns the extracted keys that no locale file translates.
+fn keys_missing_from_every_locale(results: &[ComparisonResult]) -> HashSet<&String> {
+    let mut per_locale = results
+        .iter()
+        .filter(|result| result.left_name == "all_strings")
+        .map(|result| result.missing_in_right.iter().collect::<HashSet<_>>());
+    let first = per_locale.next().unwrap_or_default();
+    per_locale.fold(first, |missing, locale| {
+        missing.intersection(&locale).copied().collect()
+    })
+}
+
+/// Compares the keys with each locale and each pair of locales, keeping only
+/// keys new since the baseline with `--report-new-keys-only`. Nothing is
+/// printed or written; fails if `--report-new-keys-only` has no baseline.
+fn process_keys(
+    all_strings: &HashSet<String>,
+    locales: &[Locale],
+    baseline: Option<&HashSet<String>>,
+    args: &Args,
+) -> Result<Vec<ComparisonResult>, io::Error> {
+    let mut results: Vec<_> = locales
+        .iter()
+        .map(|locale| {
+            compare_keys(
+                "all_strings",
+                all_strings,
+                &locale.name,
+                &locale.keys,
+                args.suggest_similar,
+            )
         })
+        .collect();
+    for (i, left) in locales.iter().enumerate() {
+        for right in &locales[i + 1..] {
+            results.push(compare_keys(
+                &left.name,
+                &left.keys,
+                &right.name,
+                &right.keys,
+                args.suggest_similar,
+            ));
+        }
+    }
+    if args.report_new_keys_only {
+        let baseline = baseline.ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                "--report-new-keys-only requires a baseline",
+            )
+        })?;
+        for result in &mut results {
+            result.missing_in_right = diff_missing_keys(&result.missing_in_right, baseline);
+            result.missing_in_left = diff_missing_keys(&result.missing_in_left, baseline);
+        }
+    }
+    Ok(results)
 }
 
-fn collect_strings_from_file(dir: &Path, re: &Regex) -> Result<HashSet<String>, io::Error> {
-    let content = fs::read_to_string(dir)?;
-
-    let strings: HashSet<_> = re
-        .captures_iter(&content)
-        .filter_map(|cap| cap.get(1))
-        .filter_map(|m| {
-            let matched_string = m.as_str();
-            let start = m.start() - 1;
-
-            if matched_string.chars().all(|c| !c.is_alphabetic())
-                || (matches!(matched_string.chars().next(), Some('/' | '#'))
-                    && matched_string.chars().nth(1).is_some_and(|c| c != ' '))
-                || matched_string.contains("%Y")
-                || matched_string
-                    .chars()
-                    .any(|c| ('\u{AC00}'..='\u{D7A3}').contains(&c))
-                || matched_string.starts_with("report-")
-                || matched_string.len() == 1
-            {
-                return None;
-            }
+fn write_exports(
+    ui_repo: &Path,
+    all_strings: &HashSet<String>,
+    locales: &[(&str, &Map<String, Value>)],
+    args: &Args,
+) -> Result<(), io::Error> {
+    if let Some(ref path) = args.save_keys {
+        let repo_commit = RepoManager::head_commit(ui_repo).ok();
+        save_keys(path, all_strings, repo_commit.as_deref())?;
+    }
+    if let Some(ref path) = args.export_pot {
+        write_pot(path, all_strings)?;
+    }
+    if let Some(ref path) = args.export_xliff {
+        write_xliff(path, all_strings)?;
+    }
+    if let Some(ref path) = args.export_csv {
+        write_csv(path, all_strings, locales)?;
+    }
+    Ok(())
+}
 
-            let line_start = content[..start].rfind('\n').map_or(0, |pos| pos + 1);
-            let line_end = content[start..]
-                .find('\n')
-                .map_or(content.len(), |pos| start + pos);
-            let current_line = content[line_start..line_end].trim();
-
-            if current_line.contains("expect(")
-                || current_line.contains("feature =")
-                || current_line.contains("#[serde(rename =")
-                || current_line.contains("#[strum(serialize =")
-            {
-                return None;
-            }
+/// Prints keys with empty values when `--warn-empty-values` or
+/// `--error-empty-values` is set, returning how many should fail the run.
+fn report_empty_values(locales: &[Locale], args: &Args) -> Result<usize, io::Error> {
+    if !args.warn_empty_values && !args.error_empty_values {
+        return Ok(0);
+    }
+    let mut empty = Vec::new();
+    for locale in locales {
+        empty.push((
+            locale.name.clone(),
+            detect_empty_values(&locale.path, args.locale_format, args.read_options())?,
+        ));
+    }
+    if args.format == OutputFormat::Text {
+        print_empty_values(&empty);
+    }
+    if args.error_empty_values {
+        Ok(empty.iter().map(|(_, keys)| keys.len()).sum())
+    } else {
+        Ok(0)
+    }
+}
 
-            let preceding_lines: Vec<&str> = content[..start]
-                .lines()
-                .rev()
-                .take(4)
-                .map(str::trim)
-                .collect();
-
-            if preceding_lines
-                .first()
-                .is_some_and(|line| line.contains("text!("))
-            {
-                return Some(matched_string.to_string());
-            }
+/// Warns about the locale files last modified more than `days` days ago,
+/// returning their number.
+fn report_stale_locales(locales: &[Locale], days: u64) -> Result<usize, io::Error> {
+    let limit = Duration::from_secs(days.saturating_mul(SECONDS_PER_DAY));
+    let mut stale = 0;
+    for locale in locales {
+        let modified = fs::metadata(&locale.path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("Failed to read {}: {e}", locale.path.display()),
+                )
+            })?;
+        // a modification time in the future counts as fresh
+        let age = modified.elapsed().unwrap_or_default();
+        if age > limit {
+            eprintln!(
+                "⚠️ {} was last modified {} days ago, but the extracted keys changed since the baseline; its translations may be stale",
+                locale.name,
+                age.as_secs() / SECONDS_PER_DAY
+            );
+            stale += 1;
+        }
+    }
+    Ok(stale)
+}
 
-            (!preceding_lines.iter().enumerate().any(|(i, line)| {
-                line.contains("#[graphql(")
-                    || (i == 0 && line.contains("type="))
-                    || (i <= 1 && line.contains("anyhow!("))
-                    || (i <= 2 && line.contains("write!("))
-                    || (line.contains("format!(")
-                        && (i == 0
-                            || (i == 1
-                                && preceding_lines.first().is_some_and(|prev| prev.is_empty()))
-                            || (i == 2
-                                && preceding_lines.get(1).is_some_and(|prev| prev.is_empty()))))
-            }))
-            .then(|| matched_string.to_string())
-        })
-        .collect();
+/// Returns whether `--format` was given on the command line, before or after
+/// the subcommand.
+fn format_given(matches: &ArgMatches) -> bool {
+    matches.value_source("format") == Some(ValueSource::CommandLine)
+        || matches
+            .subcommand()
+            .is_some_and(|(_, matches)| format_given(matches))
+}
 
-    Ok(strings)
+/// Returns whether a CI service runs this process; `CI=false` counts as unset.
+fn ci_detected() -> bool {
+    CI_ENV_VARS.iter().any(|name| {
+        env::var(name).is_ok_and(|value| !value.is_empty() && !value.eq_ignore_ascii_case("false"))
+    })
 }
 
-fn extract_css_classes_and_ids(css_file_paths: &[PathBuf]) -> Result<HashSet<String>, io::Error> {
-    let class_re = Regex::new(r"(?:[a-zA-Z]+\.)?\.([a-zA-Z][a-zA-Z0-9_-]*)")
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-    let id_re = Regex::new(r"(?:[a-zA-Z]+#)?#([a-zA-Z][a-zA-Z0-9_-]*)")
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+/// Parses a ratio between 0 and 1 inclusive.
+fn parse_ratio(value: &str) -> Result<f64, String> {
+    let ratio: f64 = value.parse().map_err(|e| format!("{e}"))?;
+    if (0.0..=1.0).contains(&ratio) {
+        Ok(ratio)
+    } else {
+        Err(format!("{ratio} is not between 0 and 1"))
+    }
+}
 
-    let classes_and_ids = css_file_paths
-        .iter()
-        .filter_map(|path| fs::read_to_string(path).ok())
-        .flat_map(|content| {
-            content
-                .lines()
-                .flat_map(|line| {
-                    let mut combined_matches = Vec::new();
-
-                    combined_matches.extend(
-                        class_re
-                            .captures_iter(line)
-                            .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_owned())),
-                    );
-
-                    combined_matches.extend(
-                        id_re
-                            .captures_iter(line)
-                            .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_owned())),
-                    );
-
-                    combined_matches
-                })
-                .collect::<Vec<String>>()
-        })
-        .collect::<HashSet<String>>();
-
-    Ok(classes_and_ids)
-}
-
-fn extract_frontary_keys_from_file(path: &Path, re: &Regex) -> Result<HashSet<String>, io::Error> {
-    let content = fs::read_to_string(path)?;
-
-    let keys: HashSet<_> = re
-        .captures_iter(&content)
-        .filter_map(|cap| cap.get(1))
-        .filter_map(|m| {
-            let matched_string = m.as_str();
-            let start = m.start() - 1;
-
-            let preceding_lines: Vec<&str> = content[..start]
-                .lines()
-                .rev()
-                .take(4)
-                .map(str::trim)
-                .collect();
-
-            preceding_lines
-                .iter()
-                .enumerate()
-                .any(|(i, line)| {
-                    (i == 0 && line.contains("ViewString::Key"))
-                        || (line.contains("text!")
-                            && (i == 0
-                                || (i > 0
-                                    && preceding_lines
-                                        .iter()
-                                        .find(|&&l| !l.is_empty())
-                                        .is_some_and(|prev| prev.contains("ctx.props()")))))
-                })
-                .then(|| matched_string.to_string())
+/// Parses an ISO 8601 date-time with an offset, e.g. `2024-05-01T09:00:00Z`,
+/// or a date, taken as midnight UTC.
+fn parse_since(value: &str) -> Result<SystemTime, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|date| date.and_time(NaiveTime::MIN).and_utc())
         })
-        .collect();
+        .map(SystemTime::from)
+        .map_err(|_| "expected a date such as 2024-05-01 or 2024-05-01T09:00:00Z".to_string())
+}
 
-    Ok(keys)
+/// Returns `--locale-dir` if given, otherwise the directory detected in the UI
+/// repository, falling back to `langs`.
+fn locale_dir(ui_repo: &Path, configured: Option<&Path>) -> PathBuf {
+    if let Some(dir) = configured {
+        return dir.to_path_buf();
+    }
+    if let Some(dir) = discover_locale_dir(ui_repo) {
+        eprintln!("🔍 Auto-detected locale directory: {}", dir.display());
+        return dir;
+    }
+    ui_repo.join("langs")
 }
 
-fn print_missing(
-    from_name: &str,
-    to_name: &str,
-    from_set: &HashSet<String>,
-    to_set: &HashSet<String>,
+/// Moves fixed exclusions and CSS selectors from `strings` into `filtered`.
+fn exclude_fixed_and_css(
+    strings: &mut HashSet<String>,
+    filtered: &mut HashMap<String, FilterReason>,
+    css_ids: &HashSet<String>,
+    options: &ExtractOptions,
 ) {
-    let missing = from_set
-        .difference(to_set)
-        .fold(String::new(), |mut acc, key| {
-            acc.push_str("  - ");
-            acc.push_str(key);
-            acc.push('\n');
-            acc
-        });
-
-    if missing.is_empty() {
-        println!("No keys from `{from_name}` are missing in `{to_name}`.");
-    } else {
-        println!("Keys from `{from_name}` missing in `{to_name}`:\n{missing}");
+    strings.retain(|s| {
+        let reason = if options.is_included(s) {
+            return true;
+        } else if FIXED_EXCLUDED_STRINGS.iter().any(|&e| e == s) {
+            FilterReason::FixedExclusion
+        } else if css_ids.contains(s) {
+            FilterReason::CssSelector
+        } else {
+            return true;
+        };
+        filtered.entry(s.clone()).or_insert(reason);
+        false
+    });
+}
+
+/// Returns the tag, revision, version, or branch a `frontary` dependency entry
+/// pins.
+fn frontary_reference(frontary: &TomlValue) -> Option<String> {
+    if let Some(version) = frontary.as_str() {
+        return Some(version.to_string());
+    }
+    let table = frontary.as_table()?;
+    if let Some(reference) = ["tag", "rev", "version"]
+        .iter()
+        .find_map(|key| table.get(*key).and_then(TomlValue::as_str))
+    {
+        return Some(reference.to_string());
+    }
+    let branch = table.get("branch").and_then(TomlValue::as_str)?;
+    eprintln!("🌿 frontary follows branch {branch}; its latest commit may change between runs");
+    Some(branch.to_string())
+}
+
+fn read_cargo_toml(cargo_toml: &Path) -> Result<TomlValue, io::Error> {
+    let toml_str = fs::read_to_string(cargo_toml).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("Failed to read {}: {e}", cargo_toml.display()),
+        )
+    })?;
+
+    toml::from_str(&toml_str)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid TOML: {e}")))
+}
+
+/// Looks up `frontary` in `[workspace.dependencies]` of the nearest workspace
+/// root at or above `ui_root`, for members that inherit it with
+/// `frontary.workspace = true`.
+fn workspace_frontary_reference(ui_root: &Path) -> Result<Option<String>, io::Error> {
+    for dir in ui_root.ancestors() {
+        let cargo_toml = dir.join("Cargo.toml");
+        if !cargo_toml.is_file() {
+            continue;
+        }
+        if let Some(workspace) = read_cargo_toml(&cargo_toml)?.get("workspace") {
+            return Ok(workspace
+                .get("dependencies")
+                .and_then(|deps| deps.get("frontary"))
+                .and_then(frontary_reference));
+        }
     }
+    Ok(None)
 }
 
-fn compare_keys(name1: &str, set1: &HashSet<String>, name2: &str, set2: &HashSet<String>) {
-    println!("=== {name1} vs {name2} ===");
+fn read_frontary_req(ui_root: &Path) -> Result<String, io::Error> {
+    let cargo = read_cargo_toml(&ui_root.join("Cargo.toml"))?;
+
+    let mut reference = cargo
+        .get("dependencies")
+        .and_then(|deps| deps.get("frontary"))
+        .and_then(frontary_reference);
+    if reference.is_none() {
+        reference = workspace_frontary_reference(ui_root)?;
+    }
+
+    // a `[patch.<source>]` entry takes precedence over `[dependencies]`
+    if let Some(patches) = cargo.get("patch").and_then(TomlValue::as_table) {
+        for (source, patch) in patches {
+            if let Some(patched) = patch.get("frontary").and_then(frontary_reference) {
+                eprintln!("🩹 Using frontary {patched} from [patch.{source}]");
+                reference = Some(patched);
+            }
+        }
+    }
+    reference.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "`frontary` dependency not found in Cargo.toml",
+        )
+    })
+}
 
-    // keys in set1 not in set2
-    print_missing(name1, name2, set1, set2);
+fn print_filtered(filtered: &HashMap<String, FilterReason>, kept: &HashSet<String>) {
+    println!("=== Filtered Strings ===");
 
-    // keys in set2 not in set1
-    print_missing(name2, name1, set2, set1);
+    // a string may be filtered in one place but kept elsewhere
+    let mut entries = filtered
+        .iter()
+        .filter(|(string, _)| !kept.contains(*string))
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.1.cmp(b.1).then_with(|| a.0.cmp(b.0)));
+    for (string, reason) in entries {
+        println!("  - {string:?} ({reason})");
+    }
 
     println!();
 }
@@ -1,68 +1,159 @@
 use std::env;
+use std::fs;
 use std::io::{self, Error};
 use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Arc;
 
-use git2::{BranchType, Cred, FetchOptions, RemoteCallbacks, Repository, build::RepoBuilder};
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{
+    BranchType, Cred, Direction, FetchOptions, Oid, Remote, RemoteCallbacks, Repository,
+    RepositoryState, StatusOptions,
+};
 use tempfile::TempDir;
 
 const ENV_SSH_PASSPHRASE: &str = "SSH_PASSPHRASE";
+const ENV_GIT_FETCH_WITH_CLI: &str = "CARGO_NET_GIT_FETCH_WITH_CLI";
 
+/// Paths checked out by a sparse clone: the manifests, sources, static assets,
+/// and locale files that extraction reads. Each entry is both a libgit2
+/// pathspec and a line of `.git/info/sparse-checkout`.
+const SPARSE_PATTERNS: &[&str] = &[
+    ".linguistignore",
+    "Cargo.toml",
+    "*/Cargo.toml",
+    "src/*",
+    "*/src/*",
+    "static/*",
+    "langs/*",
+    "locales/*",
+    "i18n/*",
+    "*/i18n/*",
+];
+
+/// Receives `received_objects`, `total_objects`, and `received_bytes` while a
+/// repository is being fetched.
+type ProgressCallback = Arc<dyn Fn(u32, u32, usize) + Send + Sync>;
+
+/// Clones repositories into a temporary directory that is removed once the
+/// manager and all of its clones are dropped.
+#[derive(Clone)]
 pub(crate) struct RepoManager {
-    pub(crate) temp_dir: TempDir,
+    /// Shared by clones of the manager, e.g. on other threads.
+    pub(crate) temp_dir: Arc<TempDir>,
     pub(crate) ssh_key_path: Option<PathBuf>,
+    progress: Option<ProgressCallback>,
+    https_fallback: bool,
+    sparse: bool,
 }
 
 impl RepoManager {
     pub(crate) fn new(ssh_key_path: Option<PathBuf>) -> Result<Self, io::Error> {
         TempDir::new()
             .map(|temp_dir| Self {
-                temp_dir,
+                temp_dir: Arc::new(temp_dir),
                 ssh_key_path,
+                progress: None,
+                https_fallback: false,
+                sparse: false,
             })
             .map_err(|_| Error::other("Failed to create temp dir"))
     }
 
+    /// Reports transfer progress of clones and fetches to `callback`.
+    pub(crate) fn with_progress_callback(
+        mut self,
+        callback: impl Fn(u32, u32, usize) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Stops reporting transfer progress.
+    pub(crate) fn without_progress_callback(mut self) -> Self {
+        self.progress = None;
+        self
+    }
+
+    /// Retries a failed SSH clone or fetch over HTTPS when `enabled`.
+    pub(crate) fn with_https_fallback(mut self, enabled: bool) -> Self {
+        self.https_fallback = enabled;
+        self
+    }
+
+    /// Checks out only the paths extraction needs when `enabled`, and records
+    /// them as the sparse-checkout patterns of each cloned repository.
+    pub(crate) fn with_sparse(mut self, enabled: bool) -> Self {
+        self.sparse = enabled;
+        self
+    }
+
+    /// Returns the HTTPS form of `repo_url` if it is an SSH URL and HTTPS
+    /// fallback is enabled, logging that the fallback is being attempted.
+    fn https_fallback_url(&self, repo_url: &str, err: &git2::Error) -> Option<String> {
+        if !self.https_fallback {
+            return None;
+        }
+        let https_url = ssh_to_https_url(repo_url)?;
+        eprintln!(
+            "⚠️ SSH access to {repo_url} failed ({}); trying {https_url}",
+            err.message()
+        );
+        Some(https_url)
+    }
+
     pub(crate) fn clone_repo(
         &self,
         repo_url: &str,
         dest_name: &str,
     ) -> Result<PathBuf, git2::Error> {
         let dest_path = self.temp_dir.path().join(dest_name);
-
-        let mut builder = RepoBuilder::new();
-
-        // Only set up SSH authentication if we have an SSH key
-        if let Some(ref ssh_key_path) = self.ssh_key_path {
-            // Check if the URL requires SSH authentication
-            if repo_url.starts_with("git@") {
-                let ssh_key_path = ssh_key_path.clone();
-                let passphrase = env::var(ENV_SSH_PASSPHRASE).ok();
-
-                let mut callbacks = RemoteCallbacks::new();
-                callbacks.credentials(move |_url, username_from_url, _allowed_types| {
-                    match username_from_url {
-                        Some(username) => {
-                            Cred::ssh_key(username, None, &ssh_key_path, passphrase.as_deref())
-                        }
-                        None => Err(git2::Error::from_str(
-                            "❌ Username for SSH authentication is missing",
-                        )),
-                    }
+        if use_cli_git(repo_url) {
+            eprintln!("🔧 net.git-fetch-with-cli is set; cloning with the git command");
+            return self
+                .clone_repo_via_cli(repo_url, &dest_path)
+                .map_err(|err| {
+                    git2::Error::from_str(&format!("❌ Failed to clone repository: {err}"))
                 });
+        }
 
-                let mut fetch_options = FetchOptions::new();
-                fetch_options.remote_callbacks(callbacks);
-                builder.fetch_options(fetch_options);
+        let clone = |url: &str| {
+            let mut builder = RepoBuilder::new();
+            builder.fetch_options(self.fetch_options(url)?);
+            if self.sparse {
+                let mut checkout = CheckoutBuilder::new();
+                add_sparse_paths(&mut checkout);
+                builder.with_checkout(checkout);
             }
-        } else if repo_url.starts_with("git@") {
-            return Err(git2::Error::from_str(
-                "❌ SSH URL requires an SSH key. Please provide --ssh-key argument or use HTTPS URL.",
-            ));
-        }
+            builder.clone(url, &dest_path)
+        };
+        let result = match clone(repo_url) {
+            Err(err) => match self.https_fallback_url(repo_url, &err) {
+                Some(https_url) => {
+                    // a failed clone may leave a partial checkout behind
+                    let _ = fs::remove_dir_all(&dest_path);
+                    clone(&https_url)
+                }
+                None => Err(err),
+            },
+            ok => ok,
+        };
+        let result = result
+            .and_then(|repo| {
+                if self.sparse {
+                    enable_sparse_checkout(&repo)?;
+                }
+                Ok(repo)
+            })
+            .and_then(|_| {
+                Self::verify_clone(&dest_path).inspect_err(|_| {
+                    let _ = fs::remove_dir_all(&dest_path);
+                })
+            });
 
-        match builder.clone(repo_url, &dest_path) {
-            Ok(_) => {
-                println!("✅ Successfully cloned {repo_url}");
+        match result {
+            Ok(()) => {
+                eprintln!("✅ Successfully cloned {repo_url}");
                 Ok(dest_path)
             }
             Err(err) => Err(git2::Error::from_str(&format!(
@@ -71,11 +162,173 @@ impl RepoManager {
         }
     }
 
+    /// Fetches only `sha` from `repo_url` into a new repository and checks it
+    /// out, avoiding a full clone.
+    pub(crate) fn clone_at_commit(
+        &self,
+        repo_url: &str,
+        dest_name: &str,
+        sha: &str,
+    ) -> Result<PathBuf, git2::Error> {
+        let dest_path = self.temp_dir.path().join(dest_name);
+        let repo = Repository::init(&dest_path)?;
+        if self.sparse {
+            enable_sparse_checkout(&repo)?;
+        }
+
+        let fetch = |url: &str| {
+            let mut fetch_options = self.fetch_options(url)?;
+            fetch_options.depth(1);
+            repo.remote_anonymous(url)?
+                .fetch(&[sha], Some(&mut fetch_options), None)
+        };
+        fetch(repo_url)
+            .or_else(|err| match self.https_fallback_url(repo_url, &err) {
+                Some(https_url) => fetch(&https_url),
+                None => Err(err),
+            })
+            .map_err(|err| {
+                git2::Error::from_str(&format!("❌ Failed to fetch {sha} from {repo_url}: {err}"))
+            })?;
+
+        let oid = Oid::from_str(sha)?;
+        let commit = repo.find_commit(oid)?;
+        let mut checkout = sparse_checkout_builder(&repo);
+        repo.checkout_tree(commit.as_object(), Some(checkout.force()))?;
+        repo.set_head_detached(oid)?;
+
+        eprintln!("✅ Successfully fetched {repo_url} at {sha}");
+        Ok(dest_path)
+    }
+
+    fn fetch_options(&self, repo_url: &str) -> Result<FetchOptions<'static>, git2::Error> {
+        let mut fetch_options = FetchOptions::new();
+        let mut callbacks = credential_callbacks(repo_url, self.ssh_key_path.as_deref())?;
+
+        if let Some(progress) = self.progress.clone() {
+            // git2 also reports indexing progress; only forward new objects
+            let mut last_received = None;
+            callbacks.transfer_progress(move |stats| {
+                let received = stats.received_objects();
+                if last_received != Some(received) {
+                    last_received = Some(received);
+                    progress(
+                        u32::try_from(received).unwrap_or(u32::MAX),
+                        u32::try_from(stats.total_objects()).unwrap_or(u32::MAX),
+                        stats.received_bytes(),
+                    );
+                }
+                true
+            });
+        }
+
+        fetch_options.remote_callbacks(callbacks);
+        Ok(fetch_options)
+    }
+
+    /// Clones the repository in the `git bundle` file at `bundle_path`.
+    ///
+    /// libgit2 cannot read bundles, so the `git` command clones it without a
+    /// checkout, and the working tree is checked out here, honoring `--sparse`.
+    pub(crate) fn clone_from_bundle(
+        &self,
+        bundle_path: &Path,
+        dest_name: &str,
+    ) -> Result<PathBuf, git2::Error> {
+        let dest_path = self.temp_dir.path().join(dest_name);
+        let output = process::Command::new("git")
+            .arg("clone")
+            .arg("--no-checkout")
+            .arg("--quiet")
+            .arg(bundle_path)
+            .arg(&dest_path)
+            .output()
+            .map_err(|err| git2::Error::from_str(&format!("❌ Failed to run git: {err}")))?;
+        if !output.status.success() {
+            return Err(git2::Error::from_str(&format!(
+                "❌ Failed to clone bundle {}: {}",
+                bundle_path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        self.check_out_clone(&dest_path)?;
+        eprintln!("✅ Successfully cloned bundle {}", bundle_path.display());
+        Ok(dest_path)
+    }
+
+    /// Clones `repo_url` into `dest` with the `git` command, for setups that
+    /// set `net.git-fetch-with-cli` because libgit2 cannot use their
+    /// credentials or proxy.
+    fn clone_repo_via_cli(&self, repo_url: &str, dest: &Path) -> Result<PathBuf, io::Error> {
+        let mut command = process::Command::new("git");
+        command
+            .arg("clone")
+            .arg("--no-checkout")
+            .arg("--quiet")
+            .arg(repo_url)
+            .arg(dest);
+        if let Some(ref key) = self.ssh_key_path {
+            command.env(
+                "GIT_SSH_COMMAND",
+                format!("ssh -i '{}' -o IdentitiesOnly=yes", key.display()),
+            );
+        }
+        let output = command
+            .output()
+            .map_err(|e| Error::new(e.kind(), format!("Failed to run git: {e}")))?;
+        if !output.status.success() {
+            return Err(Error::other(format!(
+                "git clone {repo_url} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        self.check_out_clone(dest)
+            .map_err(|err| Error::other(err.message().to_owned()))?;
+        eprintln!("✅ Successfully cloned {repo_url}");
+        Ok(dest.to_path_buf())
+    }
+
+    /// Checks out `HEAD` of a repository cloned without a checkout by the
+    /// `git` command, honoring `--sparse`.
+    fn check_out_clone(&self, dest_path: &Path) -> Result<(), git2::Error> {
+        let repo = Repository::open(dest_path)?;
+        if self.sparse {
+            enable_sparse_checkout(&repo)?;
+        }
+        repo.checkout_head(Some(sparse_checkout_builder(&repo).force()))?;
+        Self::verify_clone(dest_path).inspect_err(|_| {
+            let _ = fs::remove_dir_all(dest_path);
+        })
+    }
+
+    /// Checks that the repository at `repo_path` resolves `HEAD` and has a
+    /// non-empty working tree, which an interrupted clone may lack.
+    pub(crate) fn verify_clone(repo_path: &Path) -> Result<(), git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        repo.head()?.peel_to_commit()?;
+        let has_files = fs::read_dir(repo_path).is_ok_and(|mut entries| {
+            entries.any(|entry| entry.is_ok_and(|entry| entry.file_name() != ".git"))
+        });
+        if has_files {
+            Ok(())
+        } else {
+            Err(git2::Error::from_str(&format!(
+                "Clone at {} has an empty working tree",
+                repo_path.display()
+            )))
+        }
+    }
+
     pub(crate) fn checkout(repo_path: &Path, reference: &str) -> Result<(), git2::Error> {
         let repo = Repository::open(repo_path)?;
-        let obj = repo.revparse_single(reference)?;
+        // a fresh clone only has a local branch for the default branch
+        let obj = repo
+            .revparse_single(reference)
+            .or_else(|_| repo.revparse_single(&format!("origin/{reference}")))?;
 
-        repo.checkout_tree(&obj, None)?;
+        repo.checkout_tree(&obj, Some(&mut sparse_checkout_builder(&repo)))?;
 
         if repo.find_branch(reference, BranchType::Local).is_ok() {
             repo.set_head(&format!("refs/heads/{reference}"))?;
@@ -87,9 +340,200 @@ impl RepoManager {
         } else {
             repo.set_head_detached(obj.id())?;
         }
+        // bring files the tree checkout left behind in line with the new HEAD
+        repo.checkout_head(Some(sparse_checkout_builder(&repo).force()))?;
+        if repo.state() != RepositoryState::Clean {
+            eprintln!(
+                "⚠️ {} is in the middle of a {:?} operation; the working tree may not match {reference}",
+                repo_path.display(),
+                repo.state()
+            );
+        }
 
         Ok(())
     }
+
+    /// Lists the tag names, e.g. `v1.0`, of the remote at `url` without
+    /// cloning it.
+    pub(crate) fn ls_remote_tags(
+        url: &str,
+        ssh_key_path: Option<&Path>,
+    ) -> Result<Vec<String>, git2::Error> {
+        let mut remote = Remote::create_detached(url)?;
+        let connection = remote.connect_auth(
+            Direction::Fetch,
+            Some(credential_callbacks(url, ssh_key_path)?),
+            None,
+        )?;
+        Ok(connection
+            .list()?
+            .iter()
+            .filter_map(|head| head.name().strip_prefix("refs/tags/"))
+            // annotated tags are also listed peeled, as `<tag>^{}`
+            .filter(|name| !name.ends_with("^{}"))
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    pub(crate) fn list_tags(repo_path: &Path) -> Result<Vec<String>, git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        let tags = repo.tag_names(None)?;
+        Ok(tags.iter().flatten().map(ToString::to_string).collect())
+    }
+
+    /// Returns whether the working tree or index has changes, including
+    /// untracked files but not ignored ones.
+    pub(crate) fn is_dirty(repo_path: &Path) -> Result<bool, git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).include_ignored(false);
+        let statuses = repo.statuses(Some(&mut options))?;
+        Ok(statuses.iter().any(|entry| !entry.status().is_empty()))
+    }
+
+    pub(crate) fn head_commit(repo_path: &Path) -> Result<String, git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        let commit = repo.head()?.peel_to_commit()?;
+        Ok(commit.id().to_string())
+    }
+}
+
+/// Returns remote callbacks that authenticate SSH URLs with `ssh_key_path`,
+/// failing if an SSH URL is given without a key.
+fn credential_callbacks(
+    repo_url: &str,
+    ssh_key_path: Option<&Path>,
+) -> Result<RemoteCallbacks<'static>, git2::Error> {
+    let mut callbacks = RemoteCallbacks::new();
+
+    // Only set up SSH authentication if we have an SSH key
+    if let Some(ssh_key_path) = ssh_key_path {
+        // Check if the URL requires SSH authentication
+        if repo_url.starts_with("git@") {
+            let ssh_key_path = ssh_key_path.to_path_buf();
+            let passphrase = env::var(ENV_SSH_PASSPHRASE).ok();
+
+            callbacks.credentials(move |_url, username_from_url, _allowed_types| {
+                match username_from_url {
+                    Some(username) => {
+                        Cred::ssh_key(username, None, &ssh_key_path, passphrase.as_deref())
+                    }
+                    None => Err(git2::Error::from_str(
+                        "❌ Username for SSH authentication is missing",
+                    )),
+                }
+            });
+        }
+    } else if repo_url.starts_with("git@") {
+        return Err(git2::Error::from_str(
+            "❌ SSH URL requires an SSH key. Please provide --ssh-key argument or use HTTPS URL.",
+        ));
+    }
+
+    Ok(callbacks)
+}
+
+/// Returns whether Cargo's `net.git-fetch-with-cli` setting is enabled for
+/// cloning `repo_url`, through `CARGO_NET_GIT_FETCH_WITH_CLI` or the nearest
+/// `.cargo/config.toml` that sets it: in a local `repo_url` directory or its
+/// ancestors, then in the current directory or its ancestors, then in
+/// `CARGO_HOME`.
+///
+/// A remote repository has no configuration before it is cloned, so only the
+/// invoking environment counts for it.
+fn use_cli_git(repo_url: &str) -> bool {
+    if let Ok(value) = env::var(ENV_GIT_FETCH_WITH_CLI) {
+        return value == "true";
+    }
+    let cargo_home = env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cargo")));
+    let local_repo = Path::new(repo_url.strip_prefix("file://").unwrap_or(repo_url));
+    let mut config_files: Vec<_> = local_repo
+        .canonicalize()
+        .ok()
+        .filter(|path| path.is_dir())
+        .into_iter()
+        .chain(env::current_dir().ok())
+        .flat_map(|start| {
+            start
+                .ancestors()
+                .flat_map(|dir| [dir.join(".cargo/config.toml"), dir.join(".cargo/config")])
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    config_files.extend(
+        cargo_home
+            .into_iter()
+            .flat_map(|home| [home.join("config.toml"), home.join("config")]),
+    );
+    config_files
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|content| toml::from_str::<toml::Value>(&content).ok())
+        .find_map(|config| config.get("net")?.get("git-fetch-with-cli")?.as_bool())
+        .unwrap_or(false)
+}
+
+fn add_sparse_paths(checkout: &mut CheckoutBuilder) {
+    for pattern in SPARSE_PATTERNS {
+        checkout.path(pattern);
+    }
+}
+
+/// Sets `core.sparseCheckout` and writes `SPARSE_PATTERNS` to
+/// `.git/info/sparse-checkout`, so later checkouts stay sparse.
+fn enable_sparse_checkout(repo: &Repository) -> Result<(), git2::Error> {
+    repo.config()?.set_bool("core.sparseCheckout", true)?;
+    let info_dir = repo.path().join("info");
+    let mut content = SPARSE_PATTERNS.join("\n");
+    content.push('\n');
+    fs::create_dir_all(&info_dir)
+        .and_then(|()| fs::write(info_dir.join("sparse-checkout"), content))
+        .map_err(|err| {
+            git2::Error::from_str(&format!("❌ Failed to write sparse-checkout file: {err}"))
+        })
+}
+
+/// Returns a checkout builder limited to the patterns in
+/// `.git/info/sparse-checkout` when `core.sparseCheckout` is set in `repo`.
+fn sparse_checkout_builder(repo: &Repository) -> CheckoutBuilder<'static> {
+    let mut checkout = CheckoutBuilder::new();
+    let sparse = repo
+        .config()
+        .and_then(|config| config.get_bool("core.sparseCheckout"))
+        .unwrap_or(false);
+    if sparse {
+        let patterns =
+            fs::read_to_string(repo.path().join("info/sparse-checkout")).unwrap_or_default();
+        for pattern in patterns.lines().map(str::trim) {
+            if !pattern.is_empty() && !pattern.starts_with(['#', '!']) {
+                checkout.path(pattern);
+            }
+        }
+    }
+    checkout
+}
+
+/// Converts an SSH URL such as `git@github.com:owner/repo.git` to
+/// `https://github.com/owner/repo.git`.
+pub(crate) fn ssh_to_https_url(ssh_url: &str) -> Option<String> {
+    let (host, path) = ssh_url.strip_prefix("git@")?.split_once(':')?;
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some(format!("https://{host}/{path}"))
+}
+
+/// Prints clone progress to stderr, updating a single line in place.
+pub(crate) fn print_clone_progress(received: u32, total: u32, bytes: usize) {
+    eprint!(
+        "\r📦 Receiving objects: {received}/{total} ({} KiB)",
+        bytes / 1024
+    );
+    if received == total {
+        eprintln!();
+    }
 }
 
 pub(crate) fn validate_ssh_key_path(ssh_key_path: &Path) -> Result<(), git2::Error> {
@@ -99,6 +543,6 @@ pub(crate) fn validate_ssh_key_path(ssh_key_path: &Path) -> Result<(), git2::Err
         ));
     }
 
-    println!("✅ SSH key found: moving forward with cloning.");
+    eprintln!("✅ SSH key found: moving forward with cloning.");
     Ok(())
 }
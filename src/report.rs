@@ -0,0 +1,484 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{self, Error, ErrorKind, Write};
+use std::path::Path;
+
+use chrono::{SecondsFormat, Utc};
+use clap::ValueEnum;
+use serde_json::{Value, json};
+
+use crate::check::suggest_similar;
+use crate::extract::FilterReason;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Markdown,
+}
+
+/// How complete a single locale file is relative to the extracted keys.
+pub(crate) struct LocaleReport {
+    pub(crate) locale: String,
+    pub(crate) total_keys: usize,
+    pub(crate) missing: Vec<String>,
+}
+
+impl LocaleReport {
+    pub(crate) fn new(locale: &str, expected: &HashSet<String>, keys: &HashSet<String>) -> Self {
+        let mut missing: Vec<_> = expected.difference(keys).cloned().collect();
+        missing.sort();
+        Self {
+            locale: locale.to_string(),
+            total_keys: expected.len(),
+            missing,
+        }
+    }
+
+    pub(crate) fn present(&self) -> usize {
+        self.total_keys - self.missing.len()
+    }
+
+    /// Percentage of expected keys present, rounded to two decimal places.
+    #[allow(clippy::cast_precision_loss)] // key counts are far below 2^52
+    pub(crate) fn completeness_pct(&self) -> f64 {
+        if self.total_keys == 0 {
+            return 100.0;
+        }
+        let pct = self.present() as f64 / self.total_keys as f64 * 100.0;
+        (pct * 100.0).round() / 100.0
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        let mut json = self.stats_json();
+        json["missing_keys"] = Value::from(self.missing.as_slice());
+        json
+    }
+
+    /// The counts of [`Self::to_json`] without the list of missing keys.
+    fn stats_json(&self) -> Value {
+        json!({
+            "locale": self.locale,
+            "total_keys": self.total_keys,
+            "present": self.present(),
+            "missing": self.missing.len(),
+            "completeness_pct": self.completeness_pct(),
+        })
+    }
+
+    fn print_text(&self) {
+        println!("=== {} ===", self.locale);
+        println!(
+            "{} of {} keys present ({:.2}% complete), {} missing",
+            self.present(),
+            self.total_keys,
+            self.completeness_pct(),
+            self.missing.len()
+        );
+        for key in &self.missing {
+            println!("  - {key}");
+        }
+        println!();
+    }
+}
+
+pub(crate) fn print_locale_reports(reports: &[LocaleReport], format: OutputFormat) {
+    match format {
+        OutputFormat::Text | OutputFormat::Markdown => {
+            reports.iter().for_each(LocaleReport::print_text);
+        }
+        OutputFormat::Json => {
+            let json = Value::Array(reports.iter().map(LocaleReport::to_json).collect());
+            println!("{json:#}");
+        }
+    }
+}
+
+/// Writes the completeness of each locale to `path` as a JSON array, for
+/// dashboards that track translation health over time.
+pub(crate) fn write_locale_stats(path: &Path, reports: &[LocaleReport]) -> Result<(), io::Error> {
+    let json = Value::Array(reports.iter().map(LocaleReport::stats_json).collect());
+    let content = serde_json::to_string_pretty(&json)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("JSON error: {e}")))?;
+    fs::write(path, content + "\n")
+        .map_err(|e| Error::new(e.kind(), format!("Failed to write {}: {e}", path.display())))?;
+    eprintln!("💾 Wrote locale statistics to {}", path.display());
+    Ok(())
+}
+
+/// The keys each of two key sets lacks from the other.
+pub(crate) struct ComparisonResult {
+    pub(crate) left_name: String,
+    pub(crate) right_name: String,
+    pub(crate) missing_in_right: HashSet<String>,
+    pub(crate) missing_in_left: HashSet<String>,
+    /// Similar keys, closest first, for each missing key that has any in the
+    /// set it is missing from. Empty unless suggestions were requested.
+    pub(crate) suggestions: HashMap<String, Vec<(String, usize)>>,
+}
+
+impl ComparisonResult {
+    pub(crate) fn to_json(&self) -> Value {
+        json!({
+            "left": self.left_name,
+            "right": self.right_name,
+            "missing_in_right": sorted(self.missing_in_right.iter()),
+            "missing_in_left": sorted(self.missing_in_left.iter()),
+        })
+    }
+
+    /// Formats a missing key as `"Cancle" (missing) — did you mean "Cancel"
+    /// (distance 2)?`, or just the key if nothing similar exists.
+    fn describe(&self, key: &str) -> String {
+        let Some(suggestions) = self.suggestions.get(key) else {
+            return key.to_string();
+        };
+        let list = suggestions
+            .iter()
+            .map(|(candidate, distance)| format!("{candidate:?} (distance {distance})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{key:?} (missing) — did you mean {list}?")
+    }
+}
+
+/// Compares two key sets, looking up similar keys for the missing ones if
+/// `suggest` is set.
+pub(crate) fn compare_keys(
+    left_name: &str,
+    left: &HashSet<String>,
+    right_name: &str,
+    right: &HashSet<String>,
+    suggest: bool,
+) -> ComparisonResult {
+    let missing_in_right: HashSet<_> = left.difference(right).cloned().collect();
+    let missing_in_left: HashSet<_> = right.difference(left).cloned().collect();
+    let mut suggestions = HashMap::new();
+    if suggest {
+        let missing = missing_in_right
+            .iter()
+            .map(|key| (key, right))
+            .chain(missing_in_left.iter().map(|key| (key, left)));
+        for (key, candidates) in missing {
+            let similar = suggest_similar(key, candidates);
+            if !similar.is_empty() {
+                let similar = similar
+                    .into_iter()
+                    .map(|(candidate, distance)| (candidate.clone(), distance))
+                    .collect();
+                suggestions.insert(key.clone(), similar);
+            }
+        }
+    }
+    ComparisonResult {
+        left_name: left_name.to_string(),
+        right_name: right_name.to_string(),
+        missing_in_right,
+        missing_in_left,
+        suggestions,
+    }
+}
+
+/// Writes key comparisons in one output format.
+pub(crate) trait Reporter {
+    fn report(&self, comparisons: &[ComparisonResult], writer: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Writes each comparison as plain text.
+pub(crate) struct TextReporter;
+
+impl TextReporter {
+    fn write_missing(
+        comparison: &ComparisonResult,
+        from_name: &str,
+        to_name: &str,
+        keys: &HashSet<String>,
+        writer: &mut dyn Write,
+    ) -> io::Result<()> {
+        if keys.is_empty() {
+            return writeln!(
+                writer,
+                "No keys from `{from_name}` are missing in `{to_name}`."
+            );
+        }
+        writeln!(writer, "Keys from `{from_name}` missing in `{to_name}`:")?;
+        // sorted so the output is identical between runs
+        for key in sorted(keys.iter()) {
+            writeln!(writer, "  - {}", comparison.describe(key))?;
+        }
+        writeln!(writer)
+    }
+}
+
+impl Reporter for TextReporter {
+    fn report(&self, comparisons: &[ComparisonResult], writer: &mut dyn Write) -> io::Result<()> {
+        for comparison in comparisons {
+            let ComparisonResult {
+                left_name,
+                right_name,
+                missing_in_right,
+                missing_in_left,
+                ..
+            } = comparison;
+            writeln!(writer, "=== {left_name} vs {right_name} ===")?;
+            Self::write_missing(comparison, left_name, right_name, missing_in_right, writer)?;
+            Self::write_missing(comparison, right_name, left_name, missing_in_left, writer)?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes all comparisons as one JSON object.
+pub(crate) struct JsonReporter {
+    /// Fields written alongside the `comparisons` array.
+    pub(crate) fields: Value,
+}
+
+impl Reporter for JsonReporter {
+    fn report(&self, comparisons: &[ComparisonResult], writer: &mut dyn Write) -> io::Result<()> {
+        let mut output = self.fields.clone();
+        output["comparisons"] = comparisons.iter().map(ComparisonResult::to_json).collect();
+        writeln!(writer, "{output:#}")
+    }
+}
+
+/// Writes each comparison as a Markdown section, e.g. for a pull request
+/// comment.
+pub(crate) struct MarkdownReporter;
+
+impl MarkdownReporter {
+    fn write_missing(
+        from_name: &str,
+        to_name: &str,
+        keys: &HashSet<String>,
+        writer: &mut dyn Write,
+    ) -> io::Result<()> {
+        if keys.is_empty() {
+            writeln!(
+                writer,
+                "No keys from `{from_name}` are missing in `{to_name}`."
+            )?;
+        } else {
+            writeln!(
+                writer,
+                "**Keys from `{from_name}` missing in `{to_name}` ({}):**",
+                keys.len()
+            )?;
+            writeln!(writer)?;
+            for key in sorted(keys.iter()) {
+                writeln!(writer, "- `{key}`")?;
+            }
+        }
+        writeln!(writer)
+    }
+}
+
+impl Reporter for MarkdownReporter {
+    fn report(&self, comparisons: &[ComparisonResult], writer: &mut dyn Write) -> io::Result<()> {
+        for comparison in comparisons {
+            let ComparisonResult {
+                left_name,
+                right_name,
+                missing_in_right,
+                missing_in_left,
+                ..
+            } = comparison;
+            writeln!(writer, "## `{left_name}` vs `{right_name}`")?;
+            writeln!(writer)?;
+            Self::write_missing(left_name, right_name, missing_in_right, writer)?;
+            Self::write_missing(right_name, left_name, missing_in_left, writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `results` in `format`; the JSON object also carries the
+/// `key_sources` fields.
+pub(crate) fn print_results(
+    results: &[ComparisonResult],
+    format: OutputFormat,
+    key_sources: &Value,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => TextReporter.report(results, writer),
+        OutputFormat::Json => JsonReporter {
+            fields: key_sources.clone(),
+        }
+        .report(results, writer),
+        OutputFormat::Markdown => MarkdownReporter.report(results, writer),
+    }
+}
+
+/// Writes the full comparison results, the extraction statistics, and the
+/// completeness of each locale to `path` as JSON, whatever `--format` is.
+pub(crate) fn write_report(
+    path: &Path,
+    results: &[ComparisonResult],
+    key_sources: &Value,
+    stats: &ExtractionStats,
+    locales: &[LocaleReport],
+) -> Result<(), io::Error> {
+    let mut fields = key_sources.clone();
+    fields["timestamp"] = Value::from(Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true));
+    fields["statistics"] = stats.to_json();
+    fields["locales"] = locales.iter().map(LocaleReport::stats_json).collect();
+    let mut content = Vec::new();
+    JsonReporter { fields }.report(results, &mut content)?;
+    fs::write(path, content)
+        .map_err(|e| Error::new(e.kind(), format!("Failed to write {}: {e}", path.display())))?;
+    eprintln!("💾 Wrote report to {}", path.display());
+    Ok(())
+}
+
+/// Describes which keys were extracted only from the UI, only from frontary,
+/// or from both.
+pub(crate) fn key_sources_json(ui: &HashSet<String>, frontary: &HashSet<String>) -> Value {
+    json!({
+        "ui_only_keys": sorted(ui.difference(frontary)),
+        "frontary_only_keys": sorted(frontary.difference(ui)),
+        "shared_keys": sorted(ui.intersection(frontary)),
+    })
+}
+
+fn sorted<'a>(keys: impl Iterator<Item = &'a String>) -> Vec<&'a String> {
+    let mut keys: Vec<_> = keys.collect();
+    keys.sort();
+    keys
+}
+
+/// Writes the comparison results to `missing_keys.json` in `dir` so later CI
+/// steps can consume them.
+pub(crate) fn write_missing_keys(dir: &Path, comparisons: &[Value]) -> Result<(), io::Error> {
+    fs::create_dir_all(dir)
+        .map_err(|e| Error::new(e.kind(), format!("Failed to create {}: {e}", dir.display())))?;
+    let path = dir.join("missing_keys.json");
+    let json = json!({
+        "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        "comparisons": comparisons,
+    });
+    let content = serde_json::to_string_pretty(&json)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("JSON error: {e}")))?;
+    fs::write(&path, content + "\n")
+        .map_err(|e| Error::new(e.kind(), format!("Failed to write {}: {e}", path.display())))?;
+    eprintln!("💾 Wrote comparison results to {}", path.display());
+    Ok(())
+}
+
+/// Counts gathered during extraction, reported by the `stats` subcommand.
+///
+/// Strings are counted once however often they occur; one kept somewhere and
+/// filtered elsewhere counts as kept, so `strings_found` is the kept strings
+/// plus the `filtered` total.
+pub(crate) struct ExtractionStats {
+    pub(crate) ui_rust_files: usize,
+    pub(crate) css_files: usize,
+    pub(crate) html_files: usize,
+    pub(crate) typescript_files: usize,
+    pub(crate) frontary_files: usize,
+    pub(crate) strings_found: usize,
+    pub(crate) filtered: BTreeMap<FilterReason, usize>,
+    pub(crate) ui_keys: usize,
+    pub(crate) frontary_keys: usize,
+    /// Keys added from `FIXED_UI_KEY` that extraction did not find itself.
+    pub(crate) fixed_ui_keys: usize,
+    /// Keys added from `FIXED_FRONTARY_KEY` that extraction did not find itself.
+    pub(crate) fixed_frontary_keys: usize,
+    pub(crate) total_keys: usize,
+}
+
+impl ExtractionStats {
+    /// Counts the filtered strings by reason, skipping those in `kept`.
+    pub(crate) fn count_filtered(
+        filtered: &HashMap<String, FilterReason>,
+        kept: &HashSet<String>,
+    ) -> BTreeMap<FilterReason, usize> {
+        let mut counts = BTreeMap::new();
+        for (string, reason) in filtered {
+            if kept.contains(string) {
+                continue;
+            }
+            *counts.entry(*reason).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    fn files_scanned(&self) -> usize {
+        self.ui_rust_files
+            + self.css_files
+            + self.html_files
+            + self.typescript_files
+            + self.frontary_files
+    }
+
+    fn filtered_total(&self) -> usize {
+        self.filtered.values().sum()
+    }
+
+    fn to_json(&self) -> Value {
+        let filtered: serde_json::Map<_, _> = self
+            .filtered
+            .iter()
+            .map(|(reason, count)| (reason.to_string(), Value::from(*count)))
+            .collect();
+        json!({
+            "files_scanned": {
+                "total": self.files_scanned(),
+                "ui_rust": self.ui_rust_files,
+                "css": self.css_files,
+                "html": self.html_files,
+                "typescript": self.typescript_files,
+                "frontary_rust": self.frontary_files,
+            },
+            "strings_found": self.strings_found,
+            "strings_filtered": self.filtered_total(),
+            "filtered_by_reason": filtered,
+            "keys": {
+                "total": self.total_keys,
+                "ui": self.ui_keys,
+                "frontary": self.frontary_keys,
+                "fixed_ui": self.fixed_ui_keys,
+                "fixed_frontary": self.fixed_frontary_keys,
+            },
+        })
+    }
+
+    fn print_text(&self) {
+        println!("=== Extraction statistics ===");
+        println!("Source files scanned: {}", self.files_scanned());
+        println!("  - UI Rust files: {}", self.ui_rust_files);
+        println!("  - CSS files: {}", self.css_files);
+        println!("  - HTML files: {}", self.html_files);
+        println!("  - TypeScript files: {}", self.typescript_files);
+        println!("  - frontary Rust files: {}", self.frontary_files);
+        println!(
+            "Distinct strings found in UI sources before filtering: {}",
+            self.strings_found
+        );
+        println!("  (a string kept in one place and filtered in another counts as kept)");
+        println!("Strings filtered: {}", self.filtered_total());
+        for (reason, count) in &self.filtered {
+            println!("  - {reason}: {count}");
+        }
+        println!("Extracted keys: {}", self.total_keys);
+        println!("  - UI: {}", self.ui_keys);
+        println!("  - frontary: {}", self.frontary_keys);
+        println!(
+            "Keys from FIXED_UI_KEY (organic extraction missed): {}",
+            self.fixed_ui_keys
+        );
+        println!(
+            "Keys from FIXED_FRONTARY_KEY (organic extraction missed): {}",
+            self.fixed_frontary_keys
+        );
+    }
+
+    pub(crate) fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Text | OutputFormat::Markdown => self.print_text(),
+            OutputFormat::Json => println!("{:#}", self.to_json()),
+        }
+    }
+}
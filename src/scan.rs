@@ -0,0 +1,274 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use toml::Value as TomlValue;
+
+use crate::config::Config;
+
+const IGNORE_FILE_NAME: &str = ".linguistignore";
+
+/// Paths excluded when the scanned repository has no `.linguistignore`.
+const DEFAULT_IGNORED_PATHS: &[&str] = &["src/triage/policy/data.rs", "src/detection/mitre.rs"];
+
+/// Collects source files under a repository root, honoring the root's
+/// `.linguistignore` file and the configured excluded directories.
+pub(crate) struct FileScanner {
+    /// Patterns from the root's `.linguistignore`, or the default ignored
+    /// paths without one.
+    ignored: Gitignore,
+    excluded_dirs: Vec<PathBuf>,
+    /// Excluded directory patterns containing `*`, matched against the
+    /// directory name.
+    excluded_dir_globs: GlobSet,
+    /// Patterns from the configured `.gitignore`-format ignore files, rooted
+    /// at the repository root.
+    gitignore: Gitignore,
+    follow_symlinks: bool,
+    max_file_size: Option<u64>,
+    /// Directory levels below each scanned directory that are descended into.
+    depth_limit: usize,
+    /// Files last modified before this time are skipped.
+    modified_since: Option<SystemTime>,
+}
+
+impl FileScanner {
+    pub(crate) fn new(root: &Path, config: &Config) -> Result<Self, io::Error> {
+        let ignore_path = root.join(IGNORE_FILE_NAME);
+        let has_ignore_file = ignore_path.is_file();
+        let mut builder = GitignoreBuilder::new(root);
+        if has_ignore_file {
+            if let Some(e) = builder.add(&ignore_path) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid pattern in {}: {e}", ignore_path.display()),
+                ));
+            }
+        } else {
+            for path in DEFAULT_IGNORED_PATHS {
+                builder
+                    .add_line(None, &format!("/{path}"))
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            }
+        }
+        let ignored = builder
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if has_ignore_file {
+            eprintln!(
+                "📄 Loaded {} pattern(s) from {}",
+                ignored.len(),
+                ignore_path.display()
+            );
+        }
+
+        let (glob_dirs, excluded_dirs): (Vec<_>, Vec<_>) = config
+            .excluded_dirs
+            .iter()
+            .partition(|dir| dir.contains('*'));
+        let mut builder = GlobSetBuilder::new();
+        for pattern in glob_dirs {
+            let glob = GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Invalid excluded directory pattern `{pattern}`: {e}"),
+                    )
+                })?;
+            builder.add(glob);
+        }
+        let excluded_dir_globs = builder
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let mut builder = GitignoreBuilder::new(root);
+        for path in &config.ignore_files {
+            if let Some(e) = builder.add(path) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid ignore file {}: {e}", path.display()),
+                ));
+            }
+        }
+        let gitignore = builder
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        Ok(Self {
+            gitignore,
+            ignored,
+            excluded_dirs: excluded_dirs.into_iter().map(PathBuf::from).collect(),
+            excluded_dir_globs,
+            follow_symlinks: config.follow_symlinks,
+            max_file_size: None,
+            depth_limit: usize::MAX,
+            modified_since: None,
+        })
+    }
+
+    /// Skips files larger than `max_file_size` bytes, printing a warning.
+    pub(crate) fn with_max_file_size(mut self, max_file_size: Option<u64>) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Descends at most `depth_limit` directory levels below each scanned
+    /// directory; without a limit, the whole tree is scanned.
+    pub(crate) fn with_depth_limit(mut self, depth_limit: Option<usize>) -> Self {
+        self.depth_limit = depth_limit.unwrap_or(usize::MAX);
+        self
+    }
+
+    /// Skips files last modified before `modified_since`.
+    pub(crate) fn with_modified_since(mut self, modified_since: Option<SystemTime>) -> Self {
+        self.modified_since = modified_since;
+        self
+    }
+
+    pub(crate) fn files_with_extension<P: AsRef<Path>>(
+        &self,
+        dir: P,
+        extension: &str,
+    ) -> Result<Vec<PathBuf>, io::Error> {
+        let mut files = Vec::new();
+        self.collect_files_with_extension(dir.as_ref(), &mut files, extension, self.depth_limit)?;
+        Ok(files)
+    }
+
+    fn collect_files_with_extension(
+        &self,
+        dir: &Path,
+        files: &mut Vec<PathBuf>,
+        extension: &str,
+        depth: usize,
+    ) -> Result<(), io::Error> {
+        fs::read_dir(dir)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| !self.is_ignored(path) && (self.follow_symlinks || !is_symlink(path)))
+            .try_for_each(|path| {
+                if path.is_dir() {
+                    if depth > 0 && !self.is_excluded_dir(&path) {
+                        self.collect_files_with_extension(&path, files, extension, depth - 1)?;
+                    }
+                } else if path.extension().and_then(|ext| ext.to_str()) == Some(extension)
+                    && !self.is_too_large(&path)
+                    && self.is_modified_since(&path)
+                {
+                    files.push(path);
+                }
+                Ok(())
+            })
+    }
+
+    fn is_too_large(&self, path: &Path) -> bool {
+        let Some(max) = self.max_file_size else {
+            return false;
+        };
+        match fs::metadata(path) {
+            Ok(metadata) if metadata.len() > max => {
+                eprintln!(
+                    "⚠️ Skipping {} ({} bytes exceeds --max-file-size {max})",
+                    path.display(),
+                    metadata.len()
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn is_modified_since(&self, path: &Path) -> bool {
+        let Some(since) = self.modified_since else {
+            return true;
+        };
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified >= since)
+    }
+
+    fn is_excluded_dir(&self, path: &Path) -> bool {
+        self.excluded_dirs.iter().any(|dir| path.ends_with(dir))
+            || path
+                .file_name()
+                .is_some_and(|name| self.excluded_dir_globs.is_match(name))
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        let is_dir = path.is_dir();
+        self.ignored.matched(path, is_dir).is_ignore()
+            || self.gitignore.matched(path, is_dir).is_ignore()
+    }
+}
+
+fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink())
+}
+
+/// Returns the `src` directories to scan in `root`: the root package's own
+/// `src` plus the `src` of every `[workspace] members` entry, with glob
+/// patterns expanded and `[workspace] exclude` entries removed.
+pub(crate) fn source_dirs(root: &Path) -> Result<Vec<PathBuf>, io::Error> {
+    let root_src = root.join("src");
+    let cargo_toml = root.join("Cargo.toml");
+    let Ok(toml_str) = fs::read_to_string(&cargo_toml) else {
+        return Ok(vec![root_src]);
+    };
+    let cargo: TomlValue = toml::from_str(&toml_str).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid TOML in {}: {e}", cargo_toml.display()),
+        )
+    })?;
+    let Some(workspace) = cargo.get("workspace") else {
+        return Ok(vec![root_src]);
+    };
+
+    let excluded = workspace_paths(root, workspace, "exclude")?;
+    let mut dirs = Vec::new();
+    if root_src.is_dir() {
+        dirs.push(root_src);
+    }
+    for member in workspace_paths(root, workspace, "members")? {
+        let member_src = member.join("src");
+        if !excluded.contains(&member) && member_src.is_dir() && !dirs.contains(&member_src) {
+            dirs.push(member_src);
+        }
+    }
+    if dirs.is_empty() {
+        dirs.push(root.join("src"));
+    }
+    Ok(dirs)
+}
+
+/// Expands the glob patterns listed under `key` in a `[workspace]` table.
+fn workspace_paths(
+    root: &Path,
+    workspace: &TomlValue,
+    key: &str,
+) -> Result<Vec<PathBuf>, io::Error> {
+    let patterns = workspace
+        .get(key)
+        .and_then(TomlValue::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(TomlValue::as_str);
+
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        let full_pattern = root.join(pattern);
+        let entries = glob::glob(&full_pattern.to_string_lossy()).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid workspace {key} pattern `{pattern}`: {e}"),
+            )
+        })?;
+        paths.extend(entries.filter_map(Result::ok).filter(|path| path.is_dir()));
+    }
+    Ok(paths)
+}